@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::io;
+
+/// A Unix group with the users which are either listed as members or have it as primary group
+#[derive(Clone)]
+pub struct Group {
+    pub name: String,
+    pub gid: u32,
+    pub members: Vec<String>,
+}
+
+/// The effective user and groups of a process
+pub struct Credentials {
+    pub uid: u32,
+    /// the effective and the supplementary groups
+    pub gids: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access {
+    pub read: bool,
+    pub write: bool,
+}
+
+impl Credentials {
+    pub fn current() -> io::Result<Self> {
+        Self::from_status("/proc/self/status")
+    }
+
+    pub fn of_process(pid: i32) -> io::Result<Self> {
+        Self::from_status(&format!("/proc/{}/status", pid))
+    }
+
+    fn from_status(path: &str) -> io::Result<Self> {
+        let status = fs::read_to_string(path)?;
+        Self::parse_status(&status).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("No uid in '{}'", path))
+        })
+    }
+
+    /// Parses the effective user and groups from the content of `/proc/<pid>/status`
+    pub fn parse_status(status: &str) -> Option<Self> {
+        // the 'Uid' and 'Gid' lines contain the real, effective, saved and filesystem ids
+        let ids = |key: &str| -> Vec<u32> {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .map(|values| {
+                    values
+                        .split_whitespace()
+                        .filter_map(|id| id.parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let uid = ids("Uid:").get(1).cloned()?;
+        let mut gids = ids("Gid:").get(1).cloned().into_iter().collect::<Vec<_>>();
+        gids.extend(ids("Groups:"));
+
+        Some(Self { uid, gids })
+    }
+
+    /// The shared memory of a segment is writable by the writer group and readable by both groups
+    pub fn access(&self, writer_gid: Option<u32>, reader_gid: Option<u32>) -> Access {
+        let is_root = self.uid == 0;
+        let is_member = |gid: Option<u32>| matches!(gid, Some(gid) if self.gids.contains(&gid));
+
+        Access {
+            read: is_root || is_member(reader_gid) || is_member(writer_gid),
+            write: is_root || is_member(writer_gid),
+        }
+    }
+}
+
+/// Reads the groups from `/etc/group` and adds the users of `/etc/passwd` with it as primary group
+pub fn groups() -> io::Result<Vec<Group>> {
+    let group_file = fs::read_to_string("/etc/group")?;
+    let passwd_file = fs::read_to_string("/etc/passwd")?;
+    Ok(parse_groups(&group_file, &passwd_file))
+}
+
+fn parse_groups(group_file: &str, passwd_file: &str) -> Vec<Group> {
+    // name:password:uid:gid:...
+    let users = passwd_file
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split(':').collect::<Vec<_>>();
+            let gid = fields.get(3)?.parse::<u32>().ok()?;
+            Some((fields[0].to_string(), gid))
+        })
+        .collect::<Vec<_>>();
+
+    // name:password:gid:member,member
+    group_file
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split(':').collect::<Vec<_>>();
+            let gid = fields.get(2)?.parse::<u32>().ok()?;
+
+            let mut members = fields
+                .get(3)
+                .map(|members| {
+                    members
+                        .split(',')
+                        .filter(|member| !member.is_empty())
+                        .map(|member| member.to_string())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            users
+                .iter()
+                .filter(|(_, primary_gid)| *primary_gid == gid)
+                .for_each(|(user, _)| {
+                    if !members.contains(user) {
+                        members.push(user.clone());
+                    }
+                });
+            members.sort();
+
+            Some(Group {
+                name: fields[0].to_string(),
+                gid,
+                members,
+            })
+        })
+        .collect()
+}
+
+/// The name of the user with the given uid from `/etc/passwd`
+pub fn user_name(uid: u32) -> Option<String> {
+    find_user_name(&fs::read_to_string("/etc/passwd").ok()?, uid)
+}
+
+fn find_user_name(passwd_file: &str, uid: u32) -> Option<String> {
+    passwd_file.lines().find_map(|line| {
+        let fields = line.split(':').collect::<Vec<_>>();
+        if fields.get(2)?.parse::<u32>().ok()? == uid {
+            Some(fields[0].to_string())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "Name:\tradar
+Umask:\t0022
+State:\tS (sleeping)
+Pid:\t4242
+Uid:\t1000\t1001\t1001\t1001
+Gid:\t100\t200\t200\t200
+Groups:\t300 400 
+VmRSS:\t   2048 kB
+";
+
+    const GROUP_FILE: &str = "root:x:0:
+iox_writer:x:300:alice,bob
+iox_reader:x:301:
+users:x:100:carol
+";
+
+    const PASSWD_FILE: &str = "root:x:0:0:root:/root:/bin/sh
+alice:x:1000:100::/home/alice:/bin/sh
+dave:x:1001:301::/home/dave:/bin/sh
+";
+
+    fn credentials(uid: u32, gids: &[u32]) -> Credentials {
+        Credentials {
+            uid,
+            gids: gids.to_vec(),
+        }
+    }
+
+    #[test]
+    fn status_with_effective_and_supplementary_ids() {
+        let credentials = Credentials::parse_status(STATUS).unwrap();
+        assert_eq!(credentials.uid, 1001);
+        assert_eq!(credentials.gids, vec![200, 300, 400]);
+    }
+
+    #[test]
+    fn status_without_uid_is_rejected() {
+        assert!(Credentials::parse_status("Name:\tradar\nGid:\t100\t100\t100\t100\n").is_none());
+
+        let credentials = Credentials::parse_status("Uid:\t0\t0\t0\t0\n").unwrap();
+        assert_eq!(credentials.uid, 0);
+        assert!(credentials.gids.is_empty());
+    }
+
+    #[test]
+    fn writer_group_may_read_and_write() {
+        let access = credentials(1000, &[100, 300]).access(Some(300), Some(301));
+        assert_eq!(
+            access,
+            Access {
+                read: true,
+                write: true
+            }
+        );
+    }
+
+    #[test]
+    fn reader_group_may_only_read() {
+        let access = credentials(1000, &[100, 301]).access(Some(300), Some(301));
+        assert_eq!(
+            access,
+            Access {
+                read: true,
+                write: false
+            }
+        );
+    }
+
+    #[test]
+    fn other_or_unknown_groups_have_no_access() {
+        let no_access = Access {
+            read: false,
+            write: false,
+        };
+        assert_eq!(
+            credentials(1000, &[100]).access(Some(300), Some(301)),
+            no_access
+        );
+        assert_eq!(credentials(1000, &[100]).access(None, None), no_access);
+    }
+
+    #[test]
+    fn root_has_full_access() {
+        let access = credentials(0, &[]).access(None, None);
+        assert_eq!(
+            access,
+            Access {
+                read: true,
+                write: true
+            }
+        );
+    }
+
+    #[test]
+    fn groups_include_the_users_with_it_as_primary_group() {
+        let groups = parse_groups(GROUP_FILE, PASSWD_FILE);
+        let members = |name: &str| {
+            let group = groups.iter().find(|group| group.name == name).unwrap();
+            (group.gid, group.members.clone())
+        };
+
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            members("iox_writer"),
+            (300, vec!["alice".to_string(), "bob".to_string()])
+        );
+        assert_eq!(members("iox_reader"), (301, vec!["dave".to_string()]));
+        assert_eq!(
+            members("users"),
+            (100, vec!["alice".to_string(), "carol".to_string()])
+        );
+        assert_eq!(members("root"), (0, vec!["root".to_string()]));
+    }
+
+    #[test]
+    fn user_name_by_uid() {
+        assert_eq!(find_user_name(PASSWD_FILE, 1001), Some("dave".to_string()));
+        assert_eq!(find_user_name(PASSWD_FILE, 4711), None);
+    }
+}
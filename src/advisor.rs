@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::{MemorySegments, ProcessList, ServiceList};
+use crate::config::Config;
+use crate::params::AdviseMemPoolsParams;
+use crate::runtime;
+use crate::types::{format_bytes, ServiceName};
+
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+
+pub struct MemPoolAdvice {
+    pub index: usize,
+    pub chunk_size: u64,
+    pub payload_size: u64,
+    pub total_number_of_chunks: u64,
+    /// the peak number of chunks in use, derived from `min_free_chunks`
+    pub peak_chunks: u64,
+    pub recommended_chunks: u64,
+}
+
+impl MemPoolAdvice {
+    pub fn total_bytes(&self) -> u64 {
+        self.total_number_of_chunks * self.chunk_size
+    }
+
+    pub fn recommended_bytes(&self) -> u64 {
+        self.recommended_chunks * self.chunk_size
+    }
+}
+
+pub struct SegmentAdvice {
+    pub segment_id: u32,
+    pub writer_group: Option<String>,
+    pub reader_group: Option<String>,
+    pub mempools: Vec<MemPoolAdvice>,
+}
+
+/// A configured service whose payload does not fit into any mempool
+pub struct UnfitService {
+    pub service: ServiceName,
+    pub payload_size: u64,
+}
+
+/// Recommends the chunk count per mempool from the peak usage and a safety margin in percent
+pub fn advise(memory: &MemorySegments, margin: f64) -> Vec<SegmentAdvice> {
+    let sample = if let Some(sample) = memory.segments.as_ref() {
+        sample
+    } else {
+        return Vec::new();
+    };
+
+    sample
+        .memory_segments()
+        .into_iter()
+        .map(|segment| SegmentAdvice {
+            segment_id: segment.segment_id(),
+            writer_group: segment.writer_group(),
+            reader_group: segment.reader_group(),
+            mempools: segment
+                .mempools()
+                .into_iter()
+                .enumerate()
+                .map(|(index, mempool)| {
+                    let total = mempool.total_number_of_chunks as u64;
+                    let peak = total.saturating_sub(mempool.min_free_chunks as u64);
+                    MemPoolAdvice {
+                        index,
+                        chunk_size: mempool.chunk_size as u64,
+                        payload_size: mempool.payload_size as u64,
+                        total_number_of_chunks: total,
+                        peak_chunks: peak,
+                        recommended_chunks: recommended_chunks(peak, margin),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The peak chunk count with the safety margin in percent, rounded up
+fn recommended_chunks(peak: u64, margin: f64) -> u64 {
+    // RouDi does not accept empty mempools
+    ((peak as f64 * (1.0 + margin / 100.0)).ceil() as u64).max(1)
+}
+
+/// The active services with a configured payload size larger than the payload size of all mempools
+pub fn unfit_services(
+    advice: &[SegmentAdvice],
+    services: &ServiceList,
+    config: &Config,
+) -> Vec<UnfitService> {
+    let largest_payload_size = advice
+        .iter()
+        .flat_map(|segment| segment.mempools.iter())
+        .map(|mempool| mempool.payload_size)
+        .max()
+        .unwrap_or(0);
+
+    config
+        .service
+        .iter()
+        .filter(|service_config| {
+            service_config.payload_size > largest_payload_size
+                && services
+                    .map
+                    .keys()
+                    .any(|service| service_config.service.matches(service))
+        })
+        .map(|service_config| UnfitService {
+            service: service_config.service.clone(),
+            payload_size: service_config.payload_size,
+        })
+        .collect()
+}
+
+pub fn to_roudi_toml(advice: &[SegmentAdvice], unfit: &[UnfitService], margin: f64) -> String {
+    let mut toml = String::new();
+    toml.push_str("# RouDi config recommended by iceray\n");
+    toml.push_str(&format!(
+        "# the chunk count is the observed peak usage with a safety margin of {}%\n\n",
+        margin
+    ));
+    toml.push_str("[general]\nversion = 1\n");
+
+    for segment in advice {
+        toml.push_str("\n[[segment]]\n");
+        if let Some(writer) = segment.writer_group.as_ref() {
+            toml.push_str(&format!("writer = \"{}\"\n", writer));
+        }
+        if let Some(reader) = segment.reader_group.as_ref() {
+            toml.push_str(&format!("reader = \"{}\"\n", reader));
+        }
+
+        for mempool in segment.mempools.iter() {
+            toml.push_str(&format!(
+                "\n[[segment.mempool]]\nsize = {}\ncount = {} # peak {} of {} chunks\n",
+                mempool.payload_size,
+                mempool.recommended_chunks,
+                mempool.peak_chunks,
+                mempool.total_number_of_chunks
+            ));
+        }
+    }
+
+    for service in unfit {
+        toml.push_str(&format!(
+            "\n# no mempool fits the {} byte payload of '{}', e.g.\n",
+            service.payload_size, service.service
+        ));
+        toml.push_str(&format!(
+            "# [[segment.mempool]]\n# size = {}\n# count = <number of chunks>\n",
+            service.payload_size.next_power_of_two()
+        ));
+    }
+
+    toml
+}
+
+pub fn report(advice: &[SegmentAdvice], unfit: &[UnfitService]) -> Vec<String> {
+    let mut report = Vec::new();
+
+    let mempools = advice
+        .iter()
+        .flat_map(|segment| {
+            segment
+                .mempools
+                .iter()
+                .map(move |mempool| (segment.segment_id, mempool))
+        })
+        .collect::<Vec<_>>();
+
+    let mut over_provisioned = mempools
+        .iter()
+        .filter(|(_, mempool)| mempool.recommended_chunks < mempool.total_number_of_chunks)
+        .collect::<Vec<_>>();
+    over_provisioned.sort_by_key(|(_, mempool)| {
+        std::cmp::Reverse(mempool.total_bytes() - mempool.recommended_bytes())
+    });
+    if !over_provisioned.is_empty() {
+        report.push("Over-provisioned mempools:".to_string());
+    }
+    for (segment_id, mempool) in over_provisioned {
+        report.push(format!(
+            " • Segment {} MemPool {} ({} payload): peak {} of {} chunks, {} wasted",
+            segment_id,
+            mempool.index,
+            format_bytes(mempool.payload_size),
+            mempool.peak_chunks,
+            mempool.total_number_of_chunks,
+            format_bytes(mempool.total_bytes() - mempool.recommended_bytes())
+        ));
+    }
+
+    let under_provisioned = mempools
+        .iter()
+        .filter(|(_, mempool)| mempool.recommended_chunks > mempool.total_number_of_chunks)
+        .collect::<Vec<_>>();
+    if !under_provisioned.is_empty() {
+        report.push("Under-provisioned mempools:".to_string());
+    }
+    for (segment_id, mempool) in under_provisioned {
+        report.push(format!(
+            " • Segment {} MemPool {} ({} payload): peak {} of {} chunks, {} recommended",
+            segment_id,
+            mempool.index,
+            format_bytes(mempool.payload_size),
+            mempool.peak_chunks,
+            mempool.total_number_of_chunks,
+            mempool.recommended_chunks
+        ));
+    }
+
+    if !unfit.is_empty() {
+        report.push("Services without fitting mempool:".to_string());
+    }
+    for service in unfit {
+        report.push(format!(
+            " • {} ({} payload)",
+            service.service,
+            format_bytes(service.payload_size)
+        ));
+    }
+
+    let (total, recommended) =
+        mempools
+            .iter()
+            .fold((0, 0), |(total, recommended), (_, mempool)| {
+                (
+                    total + mempool.total_bytes(),
+                    recommended + mempool.recommended_bytes(),
+                )
+            });
+    report.push(format!(
+        "Chunk memory: {} configured, {} recommended",
+        format_bytes(total),
+        format_bytes(recommended)
+    ));
+
+    report
+}
+
+/// Samples the introspection for the configured time and writes the recommended RouDi config
+pub fn run(
+    params: &AdviseMemPoolsParams,
+    poll_interval: Duration,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let mut memory = MemorySegments::new(params.sample_time);
+    let mut processes = ProcessList::new();
+    let mut services = ServiceList::new();
+
+    runtime::sample_for(params.sample_time, poll_interval, || {
+        memory.update();
+        processes.update();
+        services.update(&mut processes);
+        false
+    });
+
+    if memory.segments.is_none() {
+        return Err("No mempool introspection data received!".into());
+    }
+
+    let advice = advise(&memory, params.margin);
+    let unfit = unfit_services(&advice, &services, config);
+
+    let toml = to_roudi_toml(&advice, &unfit, params.margin);
+    match params.output.as_ref() {
+        Some(path) => fs::write(path, toml)?,
+        None => print!("{}", toml),
+    }
+
+    report(&advice, &unfit)
+        .iter()
+        .for_each(|line| eprintln!("{}", line));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roudi_config::RouDiConfig;
+
+    fn mempool(index: usize, payload_size: u64, total: u64, peak: u64) -> MemPoolAdvice {
+        MemPoolAdvice {
+            index,
+            chunk_size: payload_size + 64,
+            payload_size,
+            total_number_of_chunks: total,
+            peak_chunks: peak,
+            recommended_chunks: recommended_chunks(peak, 20.0),
+        }
+    }
+
+    fn advice() -> Vec<SegmentAdvice> {
+        vec![
+            SegmentAdvice {
+                segment_id: 1,
+                writer_group: Some("writer".to_string()),
+                reader_group: Some("reader".to_string()),
+                mempools: vec![mempool(0, 128, 100, 10), mempool(1, 1024, 20, 0)],
+            },
+            SegmentAdvice {
+                segment_id: 2,
+                writer_group: None,
+                reader_group: None,
+                mempools: vec![mempool(0, 64, 10, 9)],
+            },
+        ]
+    }
+
+    #[test]
+    fn recommended_chunks_round_up_the_margin() {
+        assert_eq!(recommended_chunks(10, 20.0), 12);
+        assert_eq!(recommended_chunks(10, 25.0), 13);
+        assert_eq!(recommended_chunks(9, 20.0), 11);
+        assert_eq!(recommended_chunks(7, 0.0), 7);
+    }
+
+    #[test]
+    fn recommended_chunks_without_usage_keep_one_chunk() {
+        assert_eq!(recommended_chunks(0, 0.0), 1);
+        assert_eq!(recommended_chunks(0, 20.0), 1);
+    }
+
+    #[test]
+    fn roudi_toml_is_a_valid_roudi_config() {
+        let toml = to_roudi_toml(&advice(), &[], 20.0);
+        let config: RouDiConfig = toml::from_str(&toml).unwrap();
+
+        assert_eq!(config.segment.len(), 2);
+        assert_eq!(config.segment[0].writer.as_deref(), Some("writer"));
+        assert_eq!(config.segment[0].reader.as_deref(), Some("reader"));
+        let mempools = config.segment[0]
+            .mempool
+            .iter()
+            .map(|mempool| (mempool.size, mempool.count))
+            .collect::<Vec<_>>();
+        assert_eq!(mempools, vec![(128, 12), (1024, 1)]);
+
+        assert_eq!(config.segment[1].writer, None);
+        assert_eq!(config.segment[1].mempool.len(), 1);
+        assert_eq!(config.segment[1].mempool[0].count, 11);
+    }
+
+    #[test]
+    fn roudi_toml_suggests_a_mempool_for_unfit_services() {
+        let unfit = UnfitService {
+            service: "Radar/FrontLeft/Object".parse().unwrap(),
+            payload_size: 3000,
+        };
+        let toml = to_roudi_toml(&advice(), &[unfit], 20.0);
+
+        assert!(toml.contains("safety margin of 20%"));
+        assert!(
+            toml.contains("# no mempool fits the 3000 byte payload of 'Radar/FrontLeft/Object'")
+        );
+        assert!(toml.contains("# size = 4096\n"));
+        // the suggestion is commented out and does not change the config
+        let config: RouDiConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(config.segment.len(), 2);
+    }
+}
@@ -1,16 +1,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::access::{self, Access, Credentials, Group};
+use crate::chart;
+use crate::config::{Config, Thresholds};
+use crate::history::{LeakDetector, LeakSuspect, MemPoolHistory};
+use crate::proc_info::{ProcessInfo, ProcessInfoReader};
+use crate::roudi_config::{Mismatch, RouDiConfig};
+use crate::topology::{Collapse, Topology, TopologyNode};
+
 use iceoryx_rs::introspection::{
     MemPoolIntrospection, MemPoolIntrospectionTopic, PortIntrospection, PortIntrospectionTopic,
     ProcessIntrospection, ProcessIntrospectionTopic, ServiceDescription,
 };
 use iceoryx_rs::st::{Sample, SampleReceiver};
 
-use termion::event::{Key, MouseEvent};
+use termion::event::{Key, MouseButton, MouseEvent};
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub struct TabsState<'a> {
     pub titles: Vec<&'a str>,
@@ -35,116 +46,620 @@ impl<'a> TabsState<'a> {
     }
 }
 
-pub const USED_CHUNKS_HISTORY_SIZE: usize = 120;
+pub const CHANGE_HISTORY_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Appeared,
+    Vanished,
+    Reconfigured,
+}
+
+/// A process or service which appeared or vanished or a mempool whose layout changed between two
+/// introspection samples
+pub struct Change {
+    pub time: Instant,
+    pub kind: ChangeKind,
+    pub name: String,
+}
+
+fn record_changes<K, F>(changes: &mut VecDeque<Change>, old: BTreeSet<K>, new: BTreeSet<K>, name: F)
+where
+    K: Ord,
+    F: Fn(&K) -> String,
+{
+    let time = Instant::now();
+
+    new.difference(&old).for_each(|key| {
+        changes.push_back(Change {
+            time,
+            kind: ChangeKind::Appeared,
+            name: name(key),
+        })
+    });
+    old.difference(&new).for_each(|key| {
+        changes.push_back(Change {
+            time,
+            kind: ChangeKind::Vanished,
+            name: name(key),
+        })
+    });
+
+    while changes.len() > CHANGE_HISTORY_SIZE {
+        changes.pop_front();
+    }
+}
+
+pub struct MemPoolUsage {
+    pub segment_id: u32,
+    pub index: usize,
+    pub used_chunks: u64,
+    pub total_number_of_chunks: u64,
+    pub chunk_size: u64,
+}
+
+impl MemPoolUsage {
+    pub fn usage(&self) -> f64 {
+        self.used_chunks as f64 / self.total_number_of_chunks as f64
+    }
+}
+
+/// The time span of the recent samples the usage rate is derived from
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Rates below this number of chunks per second are considered as constant usage
+const MIN_RATE: f64 = 1e-3;
+
+pub struct Forecast {
+    /// chunks per second
+    pub rate: f64,
+    /// the time until all chunks are in use at the current rate
+    pub time_to_full: Option<Duration>,
+}
+
+/// The shared memory of one or more mempools in bytes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteUsage {
+    pub total: u64,
+    pub in_use: u64,
+    /// derived from `min_free_chunks`
+    pub peak: u64,
+    pub payload: u64,
+    /// the chunk header and alignment, i.e. `chunk_size - payload_size` of each chunk
+    pub overhead: u64,
+}
+
+impl ByteUsage {
+    pub fn new(
+        chunk_size: u64,
+        payload_size: u64,
+        used_chunks: u64,
+        min_free_chunks: u64,
+        total_number_of_chunks: u64,
+    ) -> Self {
+        Self {
+            total: chunk_size * total_number_of_chunks,
+            in_use: chunk_size * used_chunks,
+            peak: chunk_size * total_number_of_chunks.saturating_sub(min_free_chunks),
+            payload: payload_size * total_number_of_chunks,
+            overhead: chunk_size.saturating_sub(payload_size) * total_number_of_chunks,
+        }
+    }
+}
+
+impl std::ops::AddAssign for ByteUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.total += other.total;
+        self.in_use += other.in_use;
+        self.peak += other.peak;
+        self.payload += other.payload;
+        self.overhead += other.overhead;
+    }
+}
+
+/// The layout of a mempool; a change means that RouDi was restarted with a different configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MemPoolFingerprint {
+    chunk_size: u64,
+    total_number_of_chunks: u64,
+    writer_group: Option<String>,
+    reader_group: Option<String>,
+}
+
+impl MemPoolFingerprint {
+    fn difference(&self, new: &MemPoolFingerprint) -> String {
+        let mut differences = Vec::new();
+        if self.chunk_size != new.chunk_size {
+            differences.push(format!(
+                "chunk size {} → {}",
+                self.chunk_size, new.chunk_size
+            ));
+        }
+        if self.total_number_of_chunks != new.total_number_of_chunks {
+            differences.push(format!(
+                "chunks {} → {}",
+                self.total_number_of_chunks, new.total_number_of_chunks
+            ));
+        }
+        if self.writer_group != new.writer_group || self.reader_group != new.reader_group {
+            differences.push("access groups".to_string());
+        }
+        differences.join(", ")
+    }
+}
 
 pub struct MemorySegments {
     sample_receiver: SampleReceiver<MemPoolIntrospectionTopic>,
     pub segments: Option<Sample<MemPoolIntrospectionTopic>>,
-    pub used_chunks_history: HashMap<(u32, usize), VecDeque<f64>>,
+    pub mempool_history: HashMap<(u32, usize), MemPoolHistory>,
+    fingerprints: HashMap<(u32, usize), MemPoolFingerprint>,
+    pub changes: VecDeque<Change>,
+    pub history_window: Duration,
     pub selection: (u32, usize),
+    pub marked: BTreeSet<(u32, usize)>,
+    pub collapsed: BTreeSet<u32>,
+    /// shows the access details of the selected segment instead of the graph
+    pub show_segment_details: bool,
+    pub last_sample: Option<Instant>,
+    pub leak_detector: Option<LeakDetector>,
+    pub leak_suspects: BTreeMap<(u32, usize), LeakSuspect>,
 }
 
 impl MemorySegments {
-    pub fn new() -> Self {
+    pub fn new(history_window: Duration) -> Self {
         let inactive_sub = MemPoolIntrospection::new().expect("Mempool introspection subscriber");
         let (subscriber, sample_receive_token) = inactive_sub.subscribe();
 
         Self {
             sample_receiver: subscriber.get_sample_receiver(sample_receive_token),
             segments: None,
-            used_chunks_history: HashMap::with_capacity(USED_CHUNKS_HISTORY_SIZE),
+            mempool_history: HashMap::new(),
+            fingerprints: HashMap::new(),
+            changes: VecDeque::new(),
+            history_window,
             selection: (0, 0),
+            marked: BTreeSet::new(),
+            collapsed: BTreeSet::new(),
+            show_segment_details: false,
+            last_sample: None,
+            leak_detector: None,
+            leak_suspects: BTreeMap::new(),
         }
     }
 
     pub fn update(&mut self) {
         if let Some(sample) = self.sample_receiver.take() {
+            let now = Instant::now();
+            let history_window = self.history_window;
+
             // update history
             sample
                 .memory_segments()
                 .into_iter()
                 .for_each(|memory_segment| {
+                    let writer_group = memory_segment.writer_group();
+                    let reader_group = memory_segment.reader_group();
                     memory_segment.mempools().into_iter().enumerate().for_each(
                         |(index, mempool)| {
-                            let history = self
-                                .used_chunks_history
-                                .entry((memory_segment.segment_id(), index))
-                                .or_insert(VecDeque::new());
+                            let key = (memory_segment.segment_id(), index);
+                            let fingerprint = MemPoolFingerprint {
+                                chunk_size: mempool.chunk_size as u64,
+                                total_number_of_chunks: mempool.total_number_of_chunks as u64,
+                                writer_group: writer_group.clone(),
+                                reader_group: reader_group.clone(),
+                            };
 
-                            if history.len() >= USED_CHUNKS_HISTORY_SIZE {
-                                history.drain(0..1);
+                            // a restart of RouDi with a different configuration must not
+                            // continue the history of an unrelated mempool
+                            let previous = self.fingerprints.insert(key, fingerprint.clone());
+                            if let Some(previous) =
+                                previous.filter(|previous| *previous != fingerprint)
+                            {
+                                let mut history = MemPoolHistory::new(history_window);
+                                history.reconfigured = Some(now);
+                                self.mempool_history.insert(key, history);
+                                self.changes.push_back(Change {
+                                    time: now,
+                                    kind: ChangeKind::Reconfigured,
+                                    name: format!(
+                                        "Segment {} MemPool {} configuration changed ({})",
+                                        key.0,
+                                        key.1,
+                                        previous.difference(&fingerprint)
+                                    ),
+                                });
+                                while self.changes.len() > CHANGE_HISTORY_SIZE {
+                                    self.changes.pop_front();
+                                }
                             }
 
-                            history.push_back(
-                                mempool.used_chunks as f64 / mempool.total_number_of_chunks as f64
-                                    * 100f64,
+                            let history = self
+                                .mempool_history
+                                .entry(key)
+                                .or_insert_with(|| MemPoolHistory::new(history_window));
+
+                            history.push(
+                                now,
+                                mempool.used_chunks as u64,
+                                mempool.min_free_chunks as u64,
+                                mempool.total_number_of_chunks as u64,
                             );
                         },
                     )
                 });
 
+            if let Some(leak_detector) = self.leak_detector {
+                self.leak_suspects = self
+                    .mempool_history
+                    .iter()
+                    .filter_map(|(mempool, history)| {
+                        leak_detector
+                            .check(&history.used_chunks, now)
+                            .map(|suspect| (*mempool, suspect))
+                    })
+                    .collect();
+            }
+
             self.segments = Some(sample);
+            self.last_sample = Some(now);
         }
     }
 
-    fn selection_next(&mut self) {
-        let sample = if let Some(sample) = self.segments.as_ref() {
-            sample
+    pub fn chunk_size(&self, mempool: (u32, usize)) -> Option<u64> {
+        self.segments
+            .as_ref()?
+            .memory_segments()
+            .into_iter()
+            .find(|segment| segment.segment_id() == mempool.0)?
+            .mempools()
+            .into_iter()
+            .nth(mempool.1)
+            .map(|info| info.chunk_size as u64)
+    }
+
+    pub fn thresholds(&self, config: &Config, mempool: (u32, usize)) -> Thresholds {
+        config.thresholds(mempool.0, self.chunk_size(mempool).unwrap_or(0))
+    }
+
+    /// The thresholds of the mempools if they are the same for all of them, since only then they
+    /// can be drawn as one line on a graph
+    pub fn shared_thresholds(
+        &self,
+        config: &Config,
+        mempools: &[(u32, usize)],
+    ) -> Option<Thresholds> {
+        let mut thresholds = mempools
+            .iter()
+            .map(|mempool| self.thresholds(config, *mempool));
+        let first = thresholds.next()?;
+        if thresholds.all(|thresholds| thresholds == first) {
+            Some(first)
         } else {
-            return;
-        };
+            None
+        }
+    }
 
-        let mut next_segment = self.selection.0;
-        let mut next_mempool = self.selection.1 as usize + 1;
-        while let Some(segment) = sample
+    /// The recent rate of change of the chunks in use and the projected time until all are in use
+    pub fn forecast(&self, mempool: (u32, usize)) -> Option<Forecast> {
+        let sample = self.segments.as_ref()?;
+        let info = sample
             .memory_segments()
             .into_iter()
-            .nth(next_segment as usize)
-        {
-            let number_of_mempools = segment.mempools().into_iter().size_hint().0;
-            if number_of_mempools > next_mempool {
-                self.selection = (next_segment, next_mempool);
-                return;
+            .find(|segment| segment.segment_id() == mempool.0)?
+            .mempools()
+            .into_iter()
+            .nth(mempool.1)?;
+        let total = info.total_number_of_chunks as f64;
+        let free = total - info.used_chunks as f64;
+
+        // the history is in percent
+        let slope = self
+            .mempool_history
+            .get(&mempool)?
+            .used_chunks
+            .slope(Instant::now(), RATE_WINDOW)?;
+        let rate = slope / 100.0 * total;
+
+        Some(if rate.abs() < MIN_RATE {
+            Forecast {
+                rate: 0.0,
+                time_to_full: None,
+            }
+        } else {
+            Forecast {
+                rate,
+                time_to_full: if rate > 0.0 {
+                    Some(Duration::from_secs_f64(free.max(0.0) / rate))
+                } else {
+                    None
+                },
             }
-            next_segment += 1;
-            next_mempool = 0;
+        })
+    }
+
+    /// The bytes of all mempools of the last sample
+    pub fn byte_usage(&self) -> ByteUsage {
+        let mut usage = ByteUsage::default();
+
+        if let Some(sample) = self.segments.as_ref() {
+            sample.memory_segments().into_iter().for_each(|segment| {
+                segment.mempools().into_iter().for_each(|mempool| {
+                    usage += ByteUsage::new(
+                        mempool.chunk_size as u64,
+                        mempool.payload_size as u64,
+                        mempool.used_chunks as u64,
+                        mempool.min_free_chunks as u64,
+                        mempool.total_number_of_chunks as u64,
+                    );
+                })
+            });
         }
+
+        usage
     }
 
-    fn selection_previous(&mut self) {
-        if self.selection.1 > 0 {
-            self.selection.1 = self.selection.1 - 1;
-            return;
+    /// All mempools of the last sample, sorted by usage with the fullest first
+    pub fn mempools_by_usage(&self) -> Vec<MemPoolUsage> {
+        let mut mempools = Vec::new();
+
+        if let Some(sample) = self.segments.as_ref() {
+            sample.memory_segments().into_iter().for_each(|segment| {
+                segment
+                    .mempools()
+                    .into_iter()
+                    .enumerate()
+                    .for_each(|(index, mempool)| {
+                        mempools.push(MemPoolUsage {
+                            segment_id: segment.segment_id(),
+                            index,
+                            used_chunks: mempool.used_chunks as u64,
+                            total_number_of_chunks: mempool.total_number_of_chunks as u64,
+                            chunk_size: mempool.chunk_size as u64,
+                        })
+                    })
+            });
         }
 
-        if self.selection.0 == 0 {
-            return;
+        mempools.sort_by(|a, b| {
+            b.usage()
+                .partial_cmp(&a.usage())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        mempools
+    }
+
+    /// All mempools in display order
+    pub fn all_mempools(&self) -> Vec<(u32, usize)> {
+        self.selectable_mempools(false)
+    }
+
+    /// The mempools shown in the usage graph; either the marked ones or just the selected one
+    pub fn graph_mempools(&self) -> Vec<(u32, usize)> {
+        if self.marked.is_empty() {
+            vec![self.selection]
+        } else {
+            self.marked.iter().cloned().collect()
+        }
+    }
+
+    /// The mempools in display order; a collapsed segment is only represented by its first mempool
+    fn selectable_mempools(&self, skip_collapsed: bool) -> Vec<(u32, usize)> {
+        let mut mempools = Vec::new();
+
+        if let Some(sample) = self.segments.as_ref() {
+            sample.memory_segments().into_iter().for_each(|segment| {
+                let segment_id = segment.segment_id();
+                let mut count = segment.mempools().into_iter().size_hint().0;
+                if skip_collapsed && self.collapsed.contains(&segment_id) {
+                    count = count.min(1);
+                }
+                mempools.extend((0..count).map(|index| (segment_id, index)));
+            });
         }
 
+        mempools
+    }
+
+    fn move_selection(&mut self, forward: bool, skip_collapsed: bool) {
+        let mempools = self.selectable_mempools(skip_collapsed);
+        let selection = self.selection;
+        let collapsed = skip_collapsed && self.collapsed.contains(&selection.0);
+        let current = mempools
+            .iter()
+            .position(|mempool| *mempool == selection || (collapsed && mempool.0 == selection.0));
+
+        let next = match current {
+            Some(index) if forward => index + 1,
+            Some(index) => index.wrapping_sub(1),
+            None => 0,
+        };
+        if let Some(mempool) = mempools.get(next) {
+            self.selection = *mempool;
+        }
+    }
+
+    /// Selects the next mempool; the mempools of collapsed segments are skipped if requested
+    fn selection_next(&mut self, skip_collapsed: bool) {
+        self.move_selection(true, skip_collapsed);
+    }
+
+    fn selection_previous(&mut self, skip_collapsed: bool) {
+        self.move_selection(false, skip_collapsed);
+    }
+
+    /// Collapses the segment of the selection into a summary row or expands it again
+    fn toggle_collapse(&mut self) {
+        let segment_id = self.selection.0;
+        if !self.collapsed.remove(&segment_id) {
+            self.collapsed.insert(segment_id);
+            self.selection.1 = 0;
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        if !self.marked.remove(&self.selection) {
+            self.marked.insert(self.selection);
+        }
+    }
+
+    /// Marks all mempools of the selected segment or unmarks them if all are already marked
+    fn toggle_segment_mark(&mut self) {
         let sample = if let Some(sample) = self.segments.as_ref() {
             sample
         } else {
             return;
         };
 
-        if let Some(segment) = sample
+        let segment_id = self.selection.0;
+        let number_of_mempools = sample
             .memory_segments()
             .into_iter()
-            .nth(self.selection.0 as usize - 1)
+            .find(|segment| segment.segment_id() == segment_id)
+            .map_or(0, |segment| segment.mempools().into_iter().size_hint().0);
+
+        let mempools = (0..number_of_mempools).map(|index| (segment_id, index));
+        if mempools
+            .clone()
+            .all(|mempool| self.marked.contains(&mempool))
         {
-            let number_of_mempools = segment.mempools().into_iter().size_hint().0;
-            if number_of_mempools > 0 {
-                self.selection = (self.selection.0 - 1, number_of_mempools - 1);
-            }
+            mempools.for_each(|mempool| {
+                self.marked.remove(&mempool);
+            });
+        } else {
+            self.marked.extend(mempools);
+        }
+    }
+
+    fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+}
+
+/// The writer or reader group of a segment as named by RouDi, with its gid and members if it is
+/// known on this host
+pub struct SegmentGroup {
+    pub name: Option<String>,
+    pub group: Option<Group>,
+}
+
+/// The user and access of iceray or a registered process, or why its credentials are unknown
+pub struct ProcessAccess {
+    pub name: String,
+    pub user: Result<(String, Access), String>,
+}
+
+/// The access details of a segment; they read `/etc/group`, `/etc/passwd` and `/proc/<pid>/status`
+/// and are therefore only resolved on ticks and selection changes while they are shown
+pub struct SegmentAccess {
+    pub segment_id: u32,
+    pub writer: SegmentGroup,
+    pub reader: SegmentGroup,
+    pub processes: Vec<ProcessAccess>,
+}
+
+impl SegmentAccess {
+    fn resolve(
+        segment_id: u32,
+        writer_group: Option<String>,
+        reader_group: Option<String>,
+        processes: &ProcessList,
+    ) -> Self {
+        let groups = access::groups().unwrap_or_default();
+        let group = |name: Option<String>| {
+            let group = name
+                .as_ref()
+                .and_then(|name| groups.iter().find(|group| &group.name == name).cloned());
+            SegmentGroup { name, group }
+        };
+        let writer = group(writer_group);
+        let reader = group(reader_group);
+
+        let writer_gid = writer.group.as_ref().map(|group| group.gid);
+        let reader_gid = reader.group.as_ref().map(|group| group.gid);
+        let process_access = |name: String, credentials: std::io::Result<Credentials>| {
+            let user = credentials
+                .map(|credentials| {
+                    (
+                        access::user_name(credentials.uid)
+                            .unwrap_or_else(|| credentials.uid.to_string()),
+                        credentials.access(writer_gid, reader_gid),
+                    )
+                })
+                .map_err(|e| e.to_string());
+            ProcessAccess { name, user }
+        };
+
+        let mut access = vec![process_access("iceray".to_string(), Credentials::current())];
+        access.extend(processes.map.iter().map(|(name, details)| {
+            process_access(
+                format!("{} ({})", name, details.pid),
+                Credentials::of_process(details.pid),
+            )
+        }));
+
+        Self {
+            segment_id,
+            writer,
+            reader,
+            processes: access,
         }
     }
 }
 
+/// The ports of a node; only ports whose introspection data carries the node name are attributed
+#[derive(Default)]
+pub struct NodeDetails {
+    pub publisher_ports: Vec<ServiceDescription>,
+    pub subscriber_ports: Vec<ServiceDescription>,
+}
+
 pub struct ProcessDetails {
     pub pid: i32,
     pub publisher_ports: Vec<ServiceDescription>,
     pub subscriber_ports: Vec<ServiceDescription>,
-    pub nodes: Vec<String>,
+    /// the nodes which own ports, by the node name in the port introspection
+    pub nodes: BTreeMap<String, NodeDetails>,
+    /// the number of nodes from the process introspection, which also counts nodes without ports
+    pub node_count: usize,
+    /// the data from `/proc/<pid>`; not available e.g. for processes in another pid namespace
+    pub info: Option<ProcessInfo>,
+    /// the CPU usage in percent of one core since the previous update
+    pub cpu_usage: Option<f64>,
+}
+
+/// The column the process list is sorted by; the resource columns are sorted with the largest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSort {
+    Name,
+    Pid,
+    Cpu,
+    Memory,
+    Threads,
+    Uptime,
+}
+
+impl ProcessSort {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessSort::Name => "Name",
+            ProcessSort::Pid => "PID",
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "RSS",
+            ProcessSort::Threads => "Threads",
+            ProcessSort::Uptime => "Uptime",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            ProcessSort::Name => ProcessSort::Pid,
+            ProcessSort::Pid => ProcessSort::Cpu,
+            ProcessSort::Cpu => ProcessSort::Memory,
+            ProcessSort::Memory => ProcessSort::Threads,
+            ProcessSort::Threads => ProcessSort::Uptime,
+            ProcessSort::Uptime => ProcessSort::Name,
+        }
+    }
 }
 
 pub struct ProcessList {
@@ -152,6 +667,12 @@ pub struct ProcessList {
     pub list: Option<Sample<ProcessIntrospectionTopic>>, //TODO use HashMap
     pub map: BTreeMap<String, ProcessDetails>,
     pub selection: (usize, String),
+    pub changes: VecDeque<Change>,
+    pub last_sample: Option<Instant>,
+    pub sort: ProcessSort,
+    /// the CPU time of each pid at the previous resource update
+    cpu_samples: HashMap<i32, (Instant, Duration)>,
+    info_reader: ProcessInfoReader,
 }
 
 impl ProcessList {
@@ -164,46 +685,123 @@ impl ProcessList {
             list: None,
             map: BTreeMap::new(),
             selection: (0, "".to_string()),
+            changes: VecDeque::new(),
+            last_sample: None,
+            sort: ProcessSort::Name,
+            cpu_samples: HashMap::new(),
+            info_reader: ProcessInfoReader::default(),
         }
     }
 
     pub fn update(&mut self) {
         if let Some(list) = self.sample_receiver.take() {
+            let old_processes = self.map.keys().cloned().collect();
             self.map.clear();
 
             list.processes().into_iter().for_each(|process| {
                 if let Some(process_name) = process.name() {
-                    let _details = self.map.entry(process_name).or_insert(ProcessDetails {
+                    self.map.entry(process_name).or_insert(ProcessDetails {
                         pid: process.pid(),
                         publisher_ports: Vec::new(),
                         subscriber_ports: Vec::new(),
-                        nodes: Vec::new(),
+                        nodes: BTreeMap::new(),
+                        node_count: process.node_count(),
+                        info: None,
+                        cpu_usage: None,
                     });
-                    // details.nodes.push(node);
                 }
             });
 
+            // the very first sample is not a change but the initial state
+            if self.last_sample.is_some() {
+                let new_processes = self.map.keys().cloned().collect();
+                record_changes(
+                    &mut self.changes,
+                    old_processes,
+                    new_processes,
+                    |process: &String| format!("process {}", process),
+                );
+            }
+            self.last_sample = Some(Instant::now());
+
             // check if selection is still at the right position
-            let found = self
-                .map
-                .keys()
-                .nth(self.selection.0)
-                .map_or(false, |key| *key == self.selection.1);
+            self.sync_selection();
+        }
+    }
 
-            if !found {
-                if let Some((index, key)) = self
-                    .map
-                    .keys()
-                    .enumerate()
-                    .find(|(_, key)| **key == self.selection.1)
-                {
-                    self.selection.0 = index;
-                    self.selection.1 = key.clone();
-                } else {
-                    self.set_selection(self.selection.0);
+    /// Reads `/proc/<pid>` of every process and derives the CPU usage from the previous read
+    pub fn update_resources(&mut self) {
+        let now = Instant::now();
+        let mut cpu_samples = HashMap::new();
+
+        for details in self.map.values_mut() {
+            details.info = self.info_reader.read(details.pid).ok();
+            details.cpu_usage = None;
+
+            if let Some(info) = details.info.as_ref() {
+                if let Some((time, cpu_time)) = self.cpu_samples.get(&details.pid) {
+                    let elapsed = now.duration_since(*time).as_secs_f64();
+                    if elapsed > 0.0 {
+                        let busy = info.cpu_time.checked_sub(*cpu_time).unwrap_or_default();
+                        details.cpu_usage = Some(busy.as_secs_f64() / elapsed * 100.0);
+                    }
                 }
+                cpu_samples.insert(details.pid, (now, info.cpu_time));
             }
         }
+        self.info_reader
+            .retain(|pid| cpu_samples.contains_key(&pid));
+        self.cpu_samples = cpu_samples;
+
+        // the order changes with the resource usage, so the selection follows the process
+        self.sync_selection();
+    }
+
+    /// The processes in the order of the sort column
+    pub fn sorted(&self) -> Vec<(&String, &ProcessDetails)> {
+        let mut processes = self.map.iter().collect::<Vec<_>>();
+
+        // a missing value counts as zero; the stable sort keeps equal entries in name order
+        let info = |details: &ProcessDetails, value: fn(&ProcessInfo) -> u64| {
+            details.info.as_ref().map_or(0, value)
+        };
+        match self.sort {
+            ProcessSort::Name => {}
+            ProcessSort::Pid => processes.sort_by_key(|(_, details)| details.pid),
+            ProcessSort::Cpu => processes.sort_by(|(_, a), (_, b)| {
+                b.cpu_usage
+                    .unwrap_or(0.0)
+                    .partial_cmp(&a.cpu_usage.unwrap_or(0.0))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            ProcessSort::Memory => processes
+                .sort_by_key(|(_, details)| std::cmp::Reverse(info(details, |info| info.rss))),
+            ProcessSort::Threads => processes
+                .sort_by_key(|(_, details)| std::cmp::Reverse(info(details, |info| info.threads))),
+            ProcessSort::Uptime => processes.sort_by_key(|(_, details)| {
+                std::cmp::Reverse(info(details, |info| info.uptime().as_secs()))
+            }),
+        }
+
+        processes
+    }
+
+    fn next_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.sync_selection();
+    }
+
+    /// Keeps the selected process selected after the order changed
+    fn sync_selection(&mut self) {
+        let position = self
+            .sorted()
+            .iter()
+            .position(|(name, _)| **name == self.selection.1);
+
+        match position {
+            Some(index) => self.selection.0 = index,
+            None => self.set_selection(self.selection.0),
+        }
     }
 
     fn set_selection(&mut self, index: usize) {
@@ -218,9 +816,9 @@ impl ProcessList {
             };
         }
 
-        if let Some(key) = self.map.keys().nth(index) {
+        if let Some(key) = self.sorted().get(index).map(|(key, _)| (*key).clone()) {
             self.selection.0 = index;
-            self.selection.1 = key.clone();
+            self.selection.1 = key;
         }
     }
 
@@ -244,6 +842,8 @@ pub struct ServiceList {
     sample_receiver: SampleReceiver<PortIntrospectionTopic>,
     pub map: BTreeMap<ServiceDescription, ServiceDetails>,
     pub selection: (usize, ServiceDescription),
+    pub changes: VecDeque<Change>,
+    pub last_sample: Option<Instant>,
 }
 
 impl ServiceList {
@@ -255,11 +855,14 @@ impl ServiceList {
             sample_receiver: subscriber.get_sample_receiver(sample_receive_token),
             map: BTreeMap::new(),
             selection: (0, ServiceDescription::default()),
+            changes: VecDeque::new(),
+            last_sample: None,
         }
     }
 
     pub fn update(&mut self, processes: &mut ProcessList) {
         if let Some(ports) = self.sample_receiver.take() {
+            let old_services = self.map.keys().cloned().collect();
             self.map.clear();
             for (_, process_details) in processes.map.iter_mut() {
                 process_details.publisher_ports.clear();
@@ -279,6 +882,14 @@ impl ServiceList {
                     if let Some(process_name) = publisher.process_name() {
                         if let Some(process_details) = processes.map.get_mut(&process_name).as_mut()
                         {
+                            if let Some(node_name) = publisher.node_name() {
+                                process_details
+                                    .nodes
+                                    .entry(node_name)
+                                    .or_default()
+                                    .publisher_ports
+                                    .push(service_description.clone());
+                            }
                             process_details.publisher_ports.push(service_description);
                         }
                         details.publisher_processes.push(process_name);
@@ -298,6 +909,14 @@ impl ServiceList {
                     if let Some(process_name) = subscriber.process_name() {
                         if let Some(process_details) = processes.map.get_mut(&process_name).as_mut()
                         {
+                            if let Some(node_name) = subscriber.node_name() {
+                                process_details
+                                    .nodes
+                                    .entry(node_name)
+                                    .or_default()
+                                    .subscriber_ports
+                                    .push(service_description.clone());
+                            }
                             process_details.subscriber_ports.push(service_description);
                         }
                         details.subscriber_processes.push(process_name);
@@ -305,6 +924,22 @@ impl ServiceList {
                 }
             });
 
+            if self.last_sample.is_some() {
+                let new_services = self.map.keys().cloned().collect();
+                record_changes(
+                    &mut self.changes,
+                    old_services,
+                    new_services,
+                    |service: &ServiceDescription| {
+                        format!(
+                            "service {} • {} • {}",
+                            service.service_id, service.instance_id, service.event_id
+                        )
+                    },
+                );
+            }
+            self.last_sample = Some(Instant::now());
+
             // check if selection is still at the right position
             let found = self
                 .map
@@ -328,6 +963,23 @@ impl ServiceList {
         }
     }
 
+    /// The services with only publishers or only subscribers and a description of the missing side
+    pub fn unmatched(&self) -> Vec<(&ServiceDescription, &'static str)> {
+        self.map
+            .iter()
+            .filter_map(|(service, details)| {
+                match (
+                    details.publisher_processes.is_empty(),
+                    details.subscriber_processes.is_empty(),
+                ) {
+                    (true, false) => Some((service, "no publisher")),
+                    (false, true) => Some((service, "no subscriber")),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
     fn set_selection(&mut self, index: usize) {
         // check if out of bounds
         let mut index = index;
@@ -357,6 +1009,157 @@ impl ServiceList {
     }
 }
 
+/// The shortest visible time span of the usage graph
+const MIN_GRAPH_SPAN: Duration = Duration::from_secs(10);
+
+/// The visible time range of the usage graph
+pub struct GraphView {
+    pub span: Duration,
+    /// the time between the right edge of the graph and now
+    pub offset: Duration,
+    history_window: Duration,
+}
+
+impl GraphView {
+    pub fn new(history_window: Duration) -> Self {
+        Self {
+            span: history_window,
+            offset: Duration::from_secs(0),
+            history_window,
+        }
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.span != self.history_window || self.offset != Duration::from_secs(0)
+    }
+
+    fn zoom_in(&mut self) {
+        self.span = (self.span / 2).max(MIN_GRAPH_SPAN.min(self.history_window));
+    }
+
+    fn zoom_out(&mut self) {
+        self.span = (self.span * 2).min(self.history_window);
+        // the wider span must still end within the history window
+        self.offset = self.offset.min(self.history_window - self.span);
+    }
+
+    /// Moves the graph by a quarter of the visible span, but not beyond the history window
+    fn pan_older(&mut self) {
+        let latest = self.history_window - self.span;
+        self.offset = (self.offset + self.span / 4).min(latest);
+    }
+
+    fn pan_newer(&mut self) {
+        self.offset = self.offset.checked_sub(self.span / 4).unwrap_or_default();
+    }
+
+    fn reset(&mut self) {
+        self.span = self.history_window;
+        self.offset = Duration::from_secs(0);
+    }
+}
+
+pub struct TopologyState {
+    pub selection: usize,
+    pub focus: Option<(TopologyNode, usize)>,
+}
+
+impl TopologyState {
+    pub fn new() -> Self {
+        Self {
+            selection: 0,
+            focus: None,
+        }
+    }
+
+    /// The graph to show, which is restricted to the neighbourhood of the focused node
+    pub fn graph(&self, processes: &ProcessList, services: &ServiceList) -> Topology {
+        let topology = Topology::new(processes, services, Collapse::None);
+        match self.focus.as_ref() {
+            Some((node, hops)) => topology.neighbourhood(node, *hops),
+            None => topology,
+        }
+    }
+
+    /// The selectable nodes in the order of the layers
+    pub fn entries(&self, processes: &ProcessList, services: &ServiceList) -> Vec<TopologyNode> {
+        let [left, middle, right] = self.graph(processes, services).layers();
+        left.into_iter().chain(middle).chain(right).collect()
+    }
+
+    fn selection_next(&mut self, processes: &ProcessList, services: &ServiceList) {
+        if self.selection + 1 < self.entries(processes, services).len() {
+            self.selection += 1;
+        }
+    }
+
+    fn selection_previous(&mut self) {
+        if self.selection > 0 {
+            self.selection -= 1;
+        }
+    }
+
+    /// Focuses the selected node; on the focused node it toggles between one and two hops
+    fn toggle_focus(&mut self, processes: &ProcessList, services: &ServiceList) {
+        let selected = if let Some(node) = self.entries(processes, services).get(self.selection) {
+            node.clone()
+        } else {
+            return;
+        };
+
+        self.focus = match self.focus.take() {
+            Some((node, hops)) if node == selected => Some((node, if hops == 1 { 2 } else { 1 })),
+            _ => Some((selected.clone(), 1)),
+        };
+
+        self.selection = self
+            .entries(processes, services)
+            .iter()
+            .position(|node| *node == selected)
+            .unwrap_or(0);
+    }
+
+    fn reset_focus(&mut self) {
+        self.focus = None;
+        self.selection = 0;
+    }
+}
+
+pub struct KioskState {
+    pub interval: Duration,
+    pub mempools: usize,
+    pub unlock_key: char,
+    step: usize,
+    last_rotation: Instant,
+}
+
+impl KioskState {
+    pub fn new(interval: Duration, mempools: usize, unlock_key: char) -> Self {
+        Self {
+            interval,
+            mempools,
+            unlock_key,
+            step: 0,
+            last_rotation: Instant::now(),
+        }
+    }
+
+    /// The rotation is Overview, the fullest mempools on the Memory page, Processes and Services
+    fn step_count(&self) -> usize {
+        self.mempools.max(1) + 3
+    }
+
+    /// The page index and the n-th fullest mempool to select for the current step
+    fn page(&self) -> (usize, Option<usize>) {
+        match self.step {
+            0 => (0, None),
+            step if step <= self.mempools.max(1) => (1, Some(step - 1)),
+            step if step == self.mempools.max(1) + 1 => (2, None),
+            _ => (3, None),
+        }
+    }
+}
+
 pub struct App<'a> {
     pub title: &'a str,
     pub should_quit: bool,
@@ -366,23 +1169,216 @@ pub struct App<'a> {
     pub memory: MemorySegments,
     pub processes: ProcessList,
     pub services: ServiceList,
+    pub topology: TopologyState,
+    pub graph: GraphView,
+    pub kiosk: Option<KioskState>,
+    pub roudi_config: Option<RouDiConfig>,
+    /// the differences of the live segments to the RouDi config
+    pub roudi_mismatches: Vec<Mismatch>,
+    /// the access details of the selected segment while they are shown
+    pub segment_access: Option<SegmentAccess>,
+    /// the mempools the active services most likely use, see `update_service_mempools`
+    pub service_mempools: BTreeMap<ServiceDescription, Vec<(u32, usize)>>,
+    pub config: Config,
+    /// a status message, e.g. the result of a chart export, with the time it was issued
+    pub message: Option<(Instant, String)>,
+    fonts: chart::Fonts,
 }
 
 impl<'a> App<'a> {
-    pub fn new(title: &'a str) -> Self {
+    pub fn new(title: &'a str, history_window: Duration) -> Self {
         App {
             title,
             should_quit: false,
             mouse_hold_position: None,
-            tabs: TabsState::new(vec!["Overview", "Memory", "Processes", "Services"]),
+            tabs: TabsState::new(vec![
+                "Overview",
+                "Memory",
+                "Processes",
+                "Services",
+                "Topology",
+                "Heatmap",
+            ]),
 
-            memory: MemorySegments::new(),
+            memory: MemorySegments::new(history_window),
             processes: ProcessList::new(),
             services: ServiceList::new(),
+            topology: TopologyState::new(),
+            graph: GraphView::new(history_window),
+            kiosk: None,
+            roudi_config: None,
+            roudi_mismatches: Vec::new(),
+            segment_access: None,
+            service_mempools: BTreeMap::new(),
+            config: Config::default(),
+            message: None,
+            fonts: chart::Fonts::default(),
+        }
+    }
+
+    /// Maps the active services to the mempools their publishers most likely use, i.e. the
+    /// smallest mempool which fits the payload size from the config in each segment the publishers
+    /// may write to. Payload sizes are not estimated from captured samples, which would require to
+    /// subscribe to every service.
+    fn update_service_mempools(&mut self) {
+        self.service_mempools.clear();
+        let sample = if let Some(sample) = self.memory.segments.as_ref() {
+            sample
+        } else {
+            return;
+        };
+        if self.config.service.is_empty() {
+            return;
+        }
+
+        let groups = access::groups().unwrap_or_default();
+        let gid = |name: Option<String>| {
+            name.and_then(|name| groups.iter().find(|group| group.name == name))
+                .map(|group| group.gid)
+        };
+        let segments = sample
+            .memory_segments()
+            .into_iter()
+            .map(|segment| {
+                let writer_gid = gid(segment.writer_group());
+                let reader_gid = gid(segment.reader_group());
+                let mempools = segment
+                    .mempools()
+                    .into_iter()
+                    .map(|mempool| mempool.payload_size as u64)
+                    .collect::<Vec<_>>();
+                (segment.segment_id(), writer_gid, reader_gid, mempools)
+            })
+            .collect::<Vec<_>>();
+
+        // the credentials of a process are read once per update, even if it has many ports
+        let processes = &self.processes.map;
+        let publisher = |name: &String| processes.get(name).map(|process| process.pid);
+        let credentials = self
+            .services
+            .map
+            .values()
+            .flat_map(|details| details.publisher_processes.iter().filter_map(publisher))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|pid| (pid, Credentials::of_process(pid).ok()))
+            .collect::<HashMap<_, _>>();
+
+        for (service, details) in self.services.map.iter() {
+            let payload_size = match self
+                .config
+                .service
+                .iter()
+                .find(|service_config| service_config.service.matches(service))
+            {
+                Some(service_config) => service_config.payload_size,
+                None => continue,
+            };
+
+            let publishers = details
+                .publisher_processes
+                .iter()
+                .filter_map(publisher)
+                .map(|pid| credentials[&pid].as_ref())
+                .collect::<Vec<_>>();
+
+            let mempools = segments
+                .iter()
+                .filter(|(_, writer_gid, reader_gid, _)| {
+                    // without known credentials, e.g. in another pid namespace, a segment can't be
+                    // ruled out
+                    publishers.is_empty()
+                        || publishers.iter().any(|credentials| match credentials {
+                            Some(credentials) => credentials.access(*writer_gid, *reader_gid).write,
+                            None => true,
+                        })
+                })
+                .filter_map(|(segment_id, _, _, mempools)| {
+                    mempools
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, mempool_payload_size)| **mempool_payload_size >= payload_size)
+                        .min_by_key(|(_, mempool_payload_size)| **mempool_payload_size)
+                        .map(|(index, _)| (*segment_id, index))
+                })
+                .collect();
+            self.service_mempools.insert(service.clone(), mempools);
+        }
+    }
+
+    /// The active services which most likely use the mempool
+    pub fn mempool_services(&self, mempool: (u32, usize)) -> Vec<&ServiceDescription> {
+        self.service_mempools
+            .iter()
+            .filter(|(_, mempools)| mempools.contains(&mempool))
+            .map(|(service, _)| service)
+            .collect()
+    }
+
+    pub fn thresholds(&self, mempool: (u32, usize)) -> Thresholds {
+        self.memory.thresholds(&self.config, mempool)
+    }
+
+    /// Exports the usage chart of the mempools shown in the graph to the current directory
+    fn export_chart(&mut self, extension: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let path = PathBuf::from(format!("iceray-chart-{}.{}", timestamp, extension));
+
+        let svg = chart::to_svg(
+            &self.memory,
+            &self.config,
+            &self.memory.graph_mempools(),
+            Instant::now(),
+        );
+        let message = match chart::write(&path, &svg, &mut self.fonts) {
+            Ok(()) => format!("Exported chart to '{}'", path.display()),
+            Err(e) => format!("Chart export failed: {}", e),
+        };
+        self.message = Some((Instant::now(), message));
+    }
+
+    pub fn start_kiosk(&mut self, kiosk: KioskState) {
+        self.kiosk = Some(kiosk);
+        self.show_kiosk_page();
+    }
+
+    fn show_kiosk_page(&mut self) {
+        let (page, mempool) = if let Some(kiosk) = self.kiosk.as_ref() {
+            kiosk.page()
+        } else {
+            return;
+        };
+
+        self.tabs.index = page;
+        if let Some(mempool) =
+            mempool.and_then(|n| self.memory.mempools_by_usage().into_iter().nth(n))
+        {
+            self.memory.selection = (mempool.segment_id, mempool.index);
+        }
+    }
+
+    fn rotate_kiosk(&mut self) {
+        if let Some(kiosk) = self.kiosk.as_mut() {
+            if kiosk.last_rotation.elapsed() < kiosk.interval {
+                return;
+            }
+            kiosk.step = (kiosk.step + 1) % kiosk.step_count();
+            kiosk.last_rotation = Instant::now();
         }
+        self.show_kiosk_page();
     }
 
     pub fn on_key(&mut self, k: Key) {
+        // in kiosk mode all keys except the unlock key are ignored
+        if let Some(kiosk) = self.kiosk.as_ref() {
+            if k == Key::Char(kiosk.unlock_key) {
+                self.kiosk = None;
+            }
+            return;
+        }
+
         match k {
             Key::Char('q') => {
                 self.should_quit = true;
@@ -394,23 +1390,67 @@ impl<'a> App<'a> {
                 self.tabs.previous();
             }
             Key::Up => match self.tabs.index {
-                1 => self.memory.selection_previous(),
+                1 => self.memory.selection_previous(true),
                 2 => self.processes.selection_previous(),
                 3 => self.services.selection_previous(),
+                4 => self.topology.selection_previous(),
+                5 => self.memory.selection_previous(false),
                 _ => (),
             },
             Key::Down => match self.tabs.index {
-                1 => self.memory.selection_next(),
+                1 => self.memory.selection_next(true),
                 2 => self.processes.selection_next(),
                 3 => self.services.selection_next(),
+                4 => self
+                    .topology
+                    .selection_next(&self.processes, &self.services),
+                5 => self.memory.selection_next(false),
                 _ => (),
             },
+            Key::Char(' ') if self.tabs.index == 1 => self.memory.toggle_mark(),
+            Key::Char('s') if self.tabs.index == 1 => self.memory.toggle_segment_mark(),
+            Key::Char('c') if self.tabs.index == 1 => self.memory.clear_marks(),
+            Key::Char('\n') if self.tabs.index == 1 => self.memory.toggle_collapse(),
+            Key::Char('d') if self.tabs.index == 1 => {
+                self.memory.show_segment_details = !self.memory.show_segment_details;
+                self.update_segment_access();
+            }
+            Key::Char('+') if self.tabs.index == 1 => self.graph.zoom_in(),
+            Key::Char('-') if self.tabs.index == 1 => self.graph.zoom_out(),
+            Key::Char('[') if self.tabs.index == 1 => self.graph.pan_older(),
+            Key::Char(']') if self.tabs.index == 1 => self.graph.pan_newer(),
+            Key::Char('0') if self.tabs.index == 1 => self.graph.reset(),
+            Key::Char('e') if self.tabs.index == 1 => self.export_chart("svg"),
+            Key::Char('E') if self.tabs.index == 1 => self.export_chart("png"),
+            Key::Char('o') if self.tabs.index == 2 => self.processes.next_sort(),
+            Key::Char('\n') if self.tabs.index == 4 => {
+                self.topology.toggle_focus(&self.processes, &self.services);
+            }
+            Key::Esc if self.tabs.index == 4 => self.topology.reset_focus(),
+            // the selection is shared with the Memory page which shows the graph
+            Key::Char('\n') if self.tabs.index == 5 => self.tabs.index = 1,
             _ => {}
         }
+
+        // the access details follow the selection to another segment without waiting for a tick
+        let shown_segment = self.segment_access.as_ref().map(|access| access.segment_id);
+        if self.memory.show_segment_details && shown_segment != Some(self.memory.selection.0) {
+            self.update_segment_access();
+        }
     }
 
     pub fn on_mouse(&mut self, m: MouseEvent) {
+        if self.kiosk.is_some() {
+            return;
+        }
+
         match m {
+            MouseEvent::Press(MouseButton::WheelUp, _, _) if self.tabs.index == 1 => {
+                self.graph.zoom_in()
+            }
+            MouseEvent::Press(MouseButton::WheelDown, _, _) if self.tabs.index == 1 => {
+                self.graph.zoom_out()
+            }
             MouseEvent::Press(_, x, y) => self.mouse_hold_position = Some((x, y)),
             MouseEvent::Hold(x, y) => self.mouse_hold_position = Some((x, y)),
             _ => self.mouse_hold_position = None,
@@ -419,7 +1459,37 @@ impl<'a> App<'a> {
 
     pub fn on_tick(&mut self) {
         self.memory.update();
+        if let Some(roudi_config) = self.roudi_config.as_ref() {
+            self.roudi_mismatches = roudi_config.check(&self.memory);
+        }
         self.processes.update();
+        self.processes.update_resources();
         self.services.update(&mut self.processes);
+        self.update_segment_access();
+        self.update_service_mempools();
+        self.rotate_kiosk();
+    }
+
+    fn update_segment_access(&mut self) {
+        if !self.memory.show_segment_details {
+            self.segment_access = None;
+            return;
+        }
+
+        let segment_id = self.memory.selection.0;
+        let segment = self.memory.segments.as_ref().and_then(|sample| {
+            sample
+                .memory_segments()
+                .into_iter()
+                .find(|segment| segment.segment_id() == segment_id)
+        });
+        self.segment_access = segment.map(|segment| {
+            SegmentAccess::resolve(
+                segment_id,
+                segment.writer_group(),
+                segment.reader_group(),
+                &self.processes,
+            )
+        });
     }
 }
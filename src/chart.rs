@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::MemorySegments;
+use crate::config::Config;
+use crate::params::ExportChartParams;
+use crate::runtime;
+use crate::ui::format_duration;
+
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 960;
+const HEIGHT: u32 = 540;
+
+/// The plot area within the image; the legend is on the right side
+const PLOT_LEFT: f64 = 70.0;
+const PLOT_RIGHT: f64 = 740.0;
+const PLOT_TOP: f64 = 50.0;
+const PLOT_BOTTOM: f64 = 490.0;
+
+const SERIES_COLORS: [&str; 8] = [
+    "#d4a017", "#1f9fbf", "#b03cb0", "#2e9e44", "#e05050", "#4a7fd4", "#7cc46a", "#d070d0",
+];
+const WARNING_COLOR: &str = "#f08030";
+const CRITICAL_COLOR: &str = "#d02020";
+
+/// Renders the usage history of the mempools as SVG with axes, thresholds and a legend
+pub fn to_svg(
+    memory: &MemorySegments,
+    config: &Config,
+    mempools: &[(u32, usize)],
+    now: Instant,
+) -> String {
+    let window = memory.history_window;
+    let x = |seconds: f64| PLOT_RIGHT + seconds / window.as_secs_f64() * (PLOT_RIGHT - PLOT_LEFT);
+    let y = |usage: f64| PLOT_BOTTOM - usage / 100.0 * (PLOT_BOTTOM - PLOT_TOP);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" "#,
+            r#"font-family="sans-serif" font-size="12">"#
+        ),
+        WIDTH, HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        WIDTH, HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="30" font-size="16" font-weight="bold">MemPool Usage - last {}</text>"#,
+        PLOT_LEFT,
+        format_duration(window)
+    );
+
+    // the grid with the usage axis on the left and the time axis at the bottom
+    for usage in (0..=100).step_by(25) {
+        let usage = usage as f64;
+        let _ = writeln!(
+            svg,
+            concat!(
+                r##"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="#dddddd"/>"##,
+                r#"<text x="{}" y="{}" text-anchor="end">{}%</text>"#
+            ),
+            PLOT_LEFT,
+            PLOT_RIGHT,
+            PLOT_LEFT - 8.0,
+            y(usage) + 4.0,
+            usage,
+            y = y(usage)
+        );
+    }
+    for tick in 0..=4 {
+        let seconds = -window.as_secs_f64() * (4 - tick) as f64 / 4.0;
+        let label = if tick == 4 {
+            "now".to_string()
+        } else {
+            format!("-{}", format_duration(window * (4 - tick) / 4))
+        };
+        let _ = writeln!(
+            svg,
+            concat!(
+                r##"<line x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="#dddddd"/>"##,
+                r#"<text x="{x}" y="{}" text-anchor="middle">{}</text>"#
+            ),
+            PLOT_TOP,
+            PLOT_BOTTOM,
+            PLOT_BOTTOM + 20.0,
+            label,
+            x = x(seconds)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+        PLOT_LEFT,
+        PLOT_TOP,
+        PLOT_RIGHT - PLOT_LEFT,
+        PLOT_BOTTOM - PLOT_TOP
+    );
+
+    let mut legend = Vec::new();
+
+    for (index, mempool) in mempools.iter().enumerate() {
+        let history = if let Some(history) = memory.mempool_history.get(mempool) {
+            history
+        } else {
+            continue;
+        };
+
+        let color = SERIES_COLORS[index % SERIES_COLORS.len()];
+        let points = history
+            .used_chunks
+            .step_points(now, |bucket| bucket.avg())
+            .iter()
+            .filter(|(seconds, _)| *seconds >= -window.as_secs_f64())
+            .map(|(seconds, usage)| format!("{:.1},{:.1}", x(*seconds), y(*usage)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            points, color
+        );
+        legend.push((
+            format!("Segment {} MemPool {}", mempool.0, mempool.1),
+            color,
+            false,
+        ));
+    }
+
+    if let Some(thresholds) = memory.shared_thresholds(config, mempools) {
+        for (name, usage, color) in [
+            ("warning", thresholds.warning, WARNING_COLOR),
+            ("critical", thresholds.critical, CRITICAL_COLOR),
+        ]
+        .iter()
+        {
+            let _ = writeln!(
+                svg,
+                concat!(
+                    r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{}" "#,
+                    r#"stroke-dasharray="6,4"/>"#
+                ),
+                PLOT_LEFT,
+                PLOT_RIGHT,
+                color,
+                y = y(*usage)
+            );
+            legend.push((format!("{} {}%", name, usage), color, true));
+        }
+    }
+
+    for (row, (name, color, dashed)) in legend.iter().enumerate() {
+        let top = PLOT_TOP + 10.0 + row as f64 * 20.0;
+        let _ = writeln!(
+            svg,
+            concat!(
+                r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{}" stroke-width="2"{}/>"#,
+                r#"<text x="{}" y="{}">{}</text>"#
+            ),
+            PLOT_RIGHT + 20.0,
+            PLOT_RIGHT + 45.0,
+            color,
+            if *dashed {
+                r#" stroke-dasharray="6,4""#
+            } else {
+                ""
+            },
+            PLOT_RIGHT + 52.0,
+            top + 4.0,
+            name,
+            y = top
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The system fonts which convert the text of a PNG chart to paths; they are loaded on the first
+/// PNG export and kept since loading them takes a while
+#[derive(Default)]
+pub struct Fonts {
+    database: Option<resvg::usvg::fontdb::Database>,
+}
+
+impl Fonts {
+    fn database(&mut self) -> Result<&resvg::usvg::fontdb::Database, Box<dyn Error>> {
+        let database = self.database.get_or_insert_with(|| {
+            let mut database = resvg::usvg::fontdb::Database::new();
+            database.load_system_fonts();
+            database
+        });
+
+        // without fonts the text would silently be missing from the image
+        if database.is_empty() {
+            return Err("No system fonts found to render the text of the PNG".into());
+        }
+        Ok(database)
+    }
+}
+
+/// Writes the chart as PNG if the path ends with `.png` and as SVG otherwise
+pub fn write(path: &Path, svg: &str, fonts: &mut Fonts) -> Result<(), Box<dyn Error>> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("png") => write_png(path, svg, fonts),
+        _ => Ok(fs::write(path, svg)?),
+    }
+}
+
+fn write_png(path: &Path, svg: &str, fonts: &mut Fonts) -> Result<(), Box<dyn Error>> {
+    use resvg::tiny_skia::{Pixmap, Transform};
+    use resvg::usvg::{Options, Tree, TreeParsing, TreeTextToPath};
+
+    let mut tree = Tree::from_str(svg, &Options::default())?;
+    tree.convert_text(fonts.database()?);
+
+    let mut pixmap = Pixmap::new(WIDTH, HEIGHT).ok_or("Could not allocate the image")?;
+    resvg::Tree::from_usvg(&tree).render(Transform::default(), &mut pixmap.as_mut());
+    pixmap.save_png(path)?;
+
+    Ok(())
+}
+
+/// Samples the introspection for the given time and writes the chart of the mempools
+pub fn run(
+    params: &ExportChartParams,
+    poll_interval: Duration,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let mut memory = MemorySegments::new(params.sample_time);
+
+    runtime::sample_for(params.sample_time, poll_interval, || {
+        memory.update();
+        false
+    });
+
+    if memory.segments.is_none() {
+        return Err("No mempool introspection data received!".into());
+    }
+
+    let mempools = if params.mempool.is_empty() {
+        memory.all_mempools()
+    } else {
+        params.mempool.clone()
+    };
+
+    write(
+        &params.output,
+        &to_svg(&memory, config, &mempools, Instant::now()),
+        &mut Fonts::default(),
+    )
+}
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::{glob_match, ServiceName};
+
+use serde::Deserialize;
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// The optional iceray configuration file
+///
+/// ```toml
+/// [[service]]
+/// service = "Radar/FrontLeft/Object"
+/// payload_size = 1024
+///
+/// [[threshold]]
+/// segment = 1
+/// chunk_size = 128
+/// warning = 50
+/// critical = 80
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub service: Vec<ServiceConfig>,
+    /// the first matching entry applies to a mempool
+    #[serde(default)]
+    pub threshold: Vec<ThresholdConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServiceConfig {
+    pub service: ServiceName,
+    /// the payload size in bytes the publishers of the service use
+    pub payload_size: u64,
+}
+
+/// The usage thresholds of the mempools matching all of the given criteria
+#[derive(Debug, Deserialize)]
+pub struct ThresholdConfig {
+    pub segment: Option<u32>,
+    pub chunk_size: Option<u64>,
+    /// a glob for `<segment id>/<chunk size>`, e.g. `*/128` or `1/*`
+    pub mempool: Option<String>,
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl ThresholdConfig {
+    fn matches(&self, segment_id: u32, chunk_size: u64) -> bool {
+        // unset criteria match every mempool
+        self.segment.iter().all(|&segment| segment == segment_id)
+            && self.chunk_size.iter().all(|&size| size == chunk_size)
+            && self
+                .mempool
+                .iter()
+                .all(|pattern| glob_match(pattern, &format!("{}/{}", segment_id, chunk_size)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// The usage in percent from which a mempool is highlighted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            warning: 80.0,
+            critical: 95.0,
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn level(&self, usage: f64) -> Level {
+        if usage >= self.critical {
+            Level::Critical
+        } else if usage >= self.warning {
+            Level::Warning
+        } else {
+            Level::Normal
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read config '{}': {}", path.display(), e))?;
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| format!("Could not parse config '{}': {}", path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| format!("Invalid config '{}': {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, threshold) in self.threshold.iter().enumerate() {
+            let in_range = |value: f64| (0.0..=100.0).contains(&value);
+            if !in_range(threshold.warning) || !in_range(threshold.critical) {
+                return Err(format!(
+                    "threshold {} is not within 0 and 100 percent",
+                    index + 1
+                ));
+            }
+            if threshold.warning > threshold.critical {
+                return Err(format!(
+                    "threshold {} has a warning level above the critical level",
+                    index + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn thresholds(&self, segment_id: u32, chunk_size: u64) -> Thresholds {
+        self.threshold
+            .iter()
+            .find(|threshold| threshold.matches(segment_id, chunk_size))
+            .map_or_else(Thresholds::default, |threshold| Thresholds {
+                warning: threshold.warning,
+                critical: threshold.critical,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Config {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn thresholds_use_the_first_matching_entry() {
+        let config = parse(
+            r#"
+            [[threshold]]
+            segment = 1
+            chunk_size = 128
+            warning = 10
+            critical = 20
+
+            [[threshold]]
+            mempool = "*/128"
+            warning = 30
+            critical = 40
+
+            [[threshold]]
+            segment = 2
+            warning = 50
+            critical = 60
+            "#,
+        );
+
+        let thresholds = |segment_id, chunk_size| {
+            let thresholds = config.thresholds(segment_id, chunk_size);
+            (thresholds.warning, thresholds.critical)
+        };
+        assert_eq!(thresholds(1, 128), (10.0, 20.0));
+        assert_eq!(thresholds(2, 128), (30.0, 40.0));
+        assert_eq!(thresholds(2, 256), (50.0, 60.0));
+        assert_eq!(thresholds(3, 256), (80.0, 95.0));
+    }
+
+    #[test]
+    fn levels_include_the_threshold() {
+        let thresholds = Thresholds {
+            warning: 50.0,
+            critical: 90.0,
+        };
+        assert_eq!(thresholds.level(49.9), Level::Normal);
+        assert_eq!(thresholds.level(50.0), Level::Warning);
+        assert_eq!(thresholds.level(90.0), Level::Critical);
+        assert_eq!(thresholds.level(100.0), Level::Critical);
+    }
+
+    #[test]
+    fn valid_thresholds_are_accepted() {
+        let config = parse(
+            r#"
+            [[threshold]]
+            warning = 0
+            critical = 100
+
+            [[threshold]]
+            warning = 80
+            critical = 80
+            "#,
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_thresholds_are_rejected() {
+        for (warning, critical) in [(90.0, 80.0), (-1.0, 50.0), (50.0, 101.0)].iter() {
+            let config = Config {
+                service: Vec::new(),
+                threshold: vec![ThresholdConfig {
+                    segment: None,
+                    chunk_size: None,
+                    mempool: None,
+                    warning: *warning,
+                    critical: *critical,
+                }],
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,636 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
+
+/// The maximal number of buckets per history; older buckets are merged when exceeded
+pub const HISTORY_CAPACITY: usize = 512;
+
+/// The aggregate of one or more consecutive samples
+#[derive(Debug, Clone, Copy)]
+pub struct Bucket {
+    pub start: Instant,
+    pub end: Instant,
+    pub min: f64,
+    pub max: f64,
+    sum: f64,
+    count: usize,
+}
+
+impl Bucket {
+    fn new(time: Instant, value: f64) -> Self {
+        Self {
+            start: time,
+            end: time,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1,
+        }
+    }
+
+    fn merge(&self, other: &Bucket) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            sum: self.sum + other.sum,
+            count: self.count + other.count,
+        }
+    }
+
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// Time weighted statistics of a history in which every bucket lasts until the next one starts
+#[derive(Debug, Clone, Copy)]
+pub struct Statistics {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub std_dev: f64,
+    pub time_above_threshold: Duration,
+    /// the number of rises above the threshold
+    pub crossings: usize,
+}
+
+/// A time based history which keeps the samples of the given window; when the capacity is exceeded,
+/// the older half is downsampled by merging adjacent buckets, which keeps the memory bounded while
+/// the recent samples stay at full resolution
+pub struct History {
+    window: Duration,
+    buckets: VecDeque<Bucket>,
+}
+
+impl History {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, time: Instant, value: f64) {
+        self.buckets.push_back(Bucket::new(time, value));
+
+        while let Some(oldest) = self.buckets.front() {
+            if time.duration_since(oldest.end) > self.window {
+                self.buckets.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.buckets.len() > HISTORY_CAPACITY {
+            self.compact();
+        }
+    }
+
+    fn compact(&mut self) {
+        let older_half = self.buckets.len() / 2;
+        let mut compacted = VecDeque::with_capacity(HISTORY_CAPACITY);
+
+        let mut older = self.buckets.drain(..older_half);
+        while let Some(first) = older.next() {
+            match older.next() {
+                Some(second) => compacted.push_back(first.merge(&second)),
+                None => compacted.push_back(first),
+            }
+        }
+        drop(older);
+
+        compacted.extend(self.buckets.drain(..));
+        self.buckets = compacted;
+    }
+
+    /// The slope of a least squares fit of the bucket averages within the window per second
+    pub fn slope(&self, now: Instant, window: Duration) -> Option<f64> {
+        let start = now.checked_sub(window)?;
+        let points = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.end >= start)
+            .map(|bucket| ((bucket.end - start).as_secs_f64(), bucket.avg()))
+            .collect::<Vec<_>>();
+
+        if points.len() < 2 {
+            return None;
+        }
+
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+            (
+                cov + (x - mean_x) * (y - mean_y),
+                var + (x - mean_x) * (x - mean_x),
+            )
+        });
+
+        if variance > 0.0 {
+            Some(covariance / variance)
+        } else {
+            None
+        }
+    }
+
+    pub fn statistics(&self, now: Instant, threshold: f64) -> Option<Statistics> {
+        let mut samples = self
+            .buckets
+            .iter()
+            .zip(
+                self.buckets
+                    .iter()
+                    .skip(1)
+                    .map(|next| next.start)
+                    .chain(std::iter::once(now)),
+            )
+            .map(|(bucket, end)| (bucket.avg(), end.saturating_duration_since(bucket.start)))
+            .collect::<Vec<_>>();
+
+        let total = samples
+            .iter()
+            .map(|(_, duration)| *duration)
+            .sum::<Duration>();
+        if total == Duration::from_secs(0) {
+            return None;
+        }
+        let weight = |duration: &Duration| duration.as_secs_f64() / total.as_secs_f64();
+
+        let mean = samples
+            .iter()
+            .map(|(value, duration)| value * weight(duration))
+            .sum::<f64>();
+        let variance = samples
+            .iter()
+            .map(|(value, duration)| (value - mean) * (value - mean) * weight(duration))
+            .sum::<f64>();
+
+        let time_above_threshold = samples
+            .iter()
+            .filter(|(value, _)| *value >= threshold)
+            .map(|(_, duration)| *duration)
+            .sum();
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| pair[0].0 < threshold && pair[1].0 >= threshold)
+            .count();
+
+        samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let percentile = |p: f64| {
+            let mut cumulated = 0.0;
+            for (value, duration) in samples.iter() {
+                cumulated += weight(duration);
+                if cumulated >= p {
+                    return *value;
+                }
+            }
+            samples.last().map_or(0.0, |(value, _)| *value)
+        };
+
+        Some(Statistics {
+            min: self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.min)
+                .fold(f64::INFINITY, f64::min),
+            max: self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.max)
+                .fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            std_dev: variance.sqrt(),
+            time_above_threshold,
+            crossings,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Bucket> {
+        self.buckets.iter()
+    }
+
+    /// Converts the buckets to a step line with the time in seconds relative to `now`; a bucket
+    /// lasts until the next one starts and the newest one is drawn up to now
+    pub fn step_points<F>(&self, now: Instant, value: F) -> Vec<(f64, f64)>
+    where
+        F: Fn(&Bucket) -> f64,
+    {
+        let relative_time = |time: Instant| -(now.duration_since(time).as_secs_f64());
+        let mut points = Vec::with_capacity(self.len() * 2);
+        let mut buckets = self.buckets.iter().peekable();
+        while let Some(bucket) = buckets.next() {
+            let end = buckets.peek().map_or(0.0, |next| relative_time(next.start));
+            points.push((relative_time(bucket.start), value(bucket)));
+            points.push((end, value(bucket)));
+        }
+        points
+    }
+}
+
+/// The number of consecutive time slices whose lowest usage has to grow for a leak suspect
+const LEAK_SLICES: usize = 4;
+
+/// Detects a steadily growing usage which never returns to its previous low, e.g. when a
+/// subscriber holds on to its samples
+#[derive(Debug, Clone, Copy)]
+pub struct LeakDetector {
+    pub window: Duration,
+    /// the minimal growth of the lowest usage within the window in percentage points
+    pub min_growth: f64,
+}
+
+/// The lowest usage in percent at the start and the end of the detection window
+#[derive(Debug, Clone, Copy)]
+pub struct LeakSuspect {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl LeakDetector {
+    pub fn check(&self, history: &History, now: Instant) -> Option<LeakSuspect> {
+        let start = now.checked_sub(self.window)?;
+        let slice = self.window.as_secs_f64() / LEAK_SLICES as f64;
+
+        // the history needs to cover roughly the whole window; samples which just dropped out of
+        // the history window, e.g. when the leak window equals it, must not prevent the detection
+        let oldest = history.iter().next()?.start;
+        if oldest.saturating_duration_since(start).as_secs_f64() > slice {
+            return None;
+        }
+
+        let mut floors = [f64::INFINITY; LEAK_SLICES];
+        for bucket in history.iter().filter(|bucket| bucket.end >= start) {
+            let index = ((bucket.end - start).as_secs_f64() / slice) as usize;
+            let floor = &mut floors[index.min(LEAK_SLICES - 1)];
+            *floor = floor.min(bucket.min);
+        }
+
+        if floors.iter().any(|floor| floor.is_infinite()) {
+            return None;
+        }
+
+        let rising = floors.windows(2).all(|pair| pair[1] >= pair[0]);
+        let suspect = LeakSuspect {
+            start: floors[0],
+            end: floors[LEAK_SLICES - 1],
+        };
+        if rising && suspect.end - suspect.start >= self.min_growth {
+            Some(suspect)
+        } else {
+            None
+        }
+    }
+}
+
+/// The number of high-water mark increases which are kept per mempool
+pub const WATERMARK_RAISE_HISTORY_SIZE: usize = 20;
+
+/// A drop of `min_free_chunks`, i.e. a new high-water mark of the chunk usage
+#[derive(Debug, Clone, Copy)]
+pub struct WatermarkRaise {
+    pub time: Instant,
+    pub wall_clock: SystemTime,
+    pub min_free_chunks: u64,
+    pub high_water_mark: f64,
+}
+
+/// The histories of a single mempool
+pub struct MemPoolHistory {
+    /// chunks in use in percent
+    pub used_chunks: History,
+    /// peak usage in percent derived from `min_free_chunks`
+    pub high_water_mark: History,
+    pub watermark_raises: VecDeque<WatermarkRaise>,
+    /// the time the mempool layout changed, i.e. when this history replaced the previous one
+    pub reconfigured: Option<Instant>,
+    min_free_chunks: Option<u64>,
+}
+
+impl MemPoolHistory {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            used_chunks: History::new(window),
+            high_water_mark: History::new(window),
+            watermark_raises: VecDeque::new(),
+            reconfigured: None,
+            min_free_chunks: None,
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        time: Instant,
+        used_chunks: u64,
+        min_free_chunks: u64,
+        total_number_of_chunks: u64,
+    ) {
+        let total = total_number_of_chunks as f64;
+        let high_water_mark = (total - min_free_chunks as f64) / total * 100f64;
+
+        self.used_chunks
+            .push(time, used_chunks as f64 / total * 100f64);
+        self.high_water_mark.push(time, high_water_mark);
+
+        if let Some(previous) = self.min_free_chunks {
+            if min_free_chunks < previous {
+                self.watermark_raises.push_back(WatermarkRaise {
+                    time,
+                    wall_clock: SystemTime::now(),
+                    min_free_chunks,
+                    high_water_mark,
+                });
+                if self.watermark_raises.len() > WATERMARK_RAISE_HISTORY_SIZE {
+                    self.watermark_raises.pop_front();
+                }
+            }
+        }
+        self.min_free_chunks = Some(min_free_chunks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_with(start: Instant, values: &[(u64, f64)], window: Duration) -> History {
+        let mut history = History::new(window);
+        for (seconds, value) in values {
+            history.push(start + Duration::from_secs(*seconds), *value);
+        }
+        history
+    }
+
+    fn leak_detector() -> LeakDetector {
+        LeakDetector {
+            window: Duration::from_secs(120),
+            min_growth: 5.0,
+        }
+    }
+
+    /// One sample per second over the default history window of two minutes
+    fn sampled(start: Instant, usage: impl Fn(u64) -> f64) -> History {
+        let values = (0..120).map(|i| (i, usage(i))).collect::<Vec<_>>();
+        history_with(start, &values, Duration::from_secs(120))
+    }
+
+    #[test]
+    fn rising_usage_is_a_leak_suspect() {
+        let start = Instant::now();
+        let history = sampled(start, |i| 10.0 + i as f64 / 6.0);
+        let now = start + Duration::from_secs(119);
+
+        let suspect = leak_detector().check(&history, now).unwrap();
+        assert!(suspect.end - suspect.start >= 5.0);
+        assert!(suspect.start < 15.0);
+        assert!(suspect.end > 20.0);
+    }
+
+    #[test]
+    fn rising_usage_with_noise_is_a_leak_suspect() {
+        let start = Instant::now();
+        let history = sampled(start, |i| 10.0 + i as f64 / 6.0 + (i % 3) as f64);
+        let now = start + Duration::from_secs(119);
+
+        assert!(leak_detector().check(&history, now).is_some());
+    }
+
+    #[test]
+    fn flat_or_recovering_usage_is_no_leak_suspect() {
+        let start = Instant::now();
+        let now = start + Duration::from_secs(119);
+
+        let flat = sampled(start, |_| 50.0);
+        assert!(leak_detector().check(&flat, now).is_none());
+
+        // the usage grows but drops back to the initial level in the last slice
+        let recovering = sampled(
+            start,
+            |i| if i > 100 { 10.0 } else { 10.0 + i as f64 / 6.0 },
+        );
+        assert!(leak_detector().check(&recovering, now).is_none());
+
+        let slow = sampled(start, |i| 10.0 + i as f64 / 100.0);
+        assert!(leak_detector().check(&slow, now).is_none());
+    }
+
+    #[test]
+    fn flat_usage_needs_a_positive_min_growth() {
+        use crate::types::parse_positive;
+
+        // without any growth required, a flat usage would be rising
+        for invalid in ["0", "-1", "NaN"].iter() {
+            assert!(parse_positive(invalid).is_err());
+        }
+
+        let start = Instant::now();
+        let now = start + Duration::from_secs(119);
+        let flat = sampled(start, |_| 50.0);
+        let sensitive = LeakDetector {
+            min_growth: parse_positive("0.01").unwrap(),
+            ..leak_detector()
+        };
+        assert!(sensitive.check(&flat, now).is_none());
+    }
+
+    #[test]
+    fn short_history_is_no_leak_suspect() {
+        let start = Instant::now();
+        let values = (0..40).map(|i| (i, i as f64)).collect::<Vec<_>>();
+        let history = history_with(start, &values, Duration::from_secs(120));
+        let now = start + Duration::from_secs(39);
+
+        assert!(leak_detector().check(&history, now).is_none());
+    }
+
+    #[test]
+    fn slope_of_a_linear_usage() {
+        let start = Instant::now();
+        let values = (0..20).map(|i| (i, 2.0 * i as f64)).collect::<Vec<_>>();
+        let history = history_with(start, &values, Duration::from_secs(120));
+        let now = start + Duration::from_secs(19);
+
+        let slope = history.slope(now, Duration::from_secs(10)).unwrap();
+        assert!((slope - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn slope_needs_two_samples_in_the_window() {
+        let start = Instant::now();
+        let history = history_with(start, &[(0, 1.0), (10, 2.0)], Duration::from_secs(120));
+        let now = start + Duration::from_secs(10);
+
+        assert!(history.slope(now, Duration::from_secs(5)).is_none());
+        assert!(history.slope(now, Duration::from_secs(10)).is_some());
+    }
+
+    #[test]
+    fn statistics_of_equally_long_samples() {
+        let start = Instant::now();
+        let history = history_with(
+            start,
+            &[(0, 10.0), (10, 20.0), (20, 40.0)],
+            Duration::from_secs(120),
+        );
+        let now = start + Duration::from_secs(30);
+
+        let statistics = history.statistics(now, 15.0).unwrap();
+        let mean = 70.0 / 3.0;
+        assert_eq!(statistics.min, 10.0);
+        assert_eq!(statistics.max, 40.0);
+        assert!((statistics.mean - mean).abs() < 1e-9);
+        let variance = [10.0, 20.0, 40.0]
+            .iter()
+            .map(|value: &f64| (value - mean).powi(2))
+            .sum::<f64>()
+            / 3.0;
+        assert!((statistics.std_dev - variance.sqrt()).abs() < 1e-9);
+        assert_eq!(statistics.p50, 20.0);
+        assert_eq!(statistics.p95, 40.0);
+        assert_eq!(statistics.p99, 40.0);
+        assert_eq!(statistics.time_above_threshold, Duration::from_secs(20));
+        assert_eq!(statistics.crossings, 1);
+    }
+
+    #[test]
+    fn statistics_are_weighted_by_time() {
+        let start = Instant::now();
+        let history = history_with(start, &[(0, 0.0), (9, 100.0)], Duration::from_secs(120));
+        let now = start + Duration::from_secs(10);
+
+        let statistics = history.statistics(now, 50.0).unwrap();
+        assert!((statistics.mean - 10.0).abs() < 1e-9);
+        assert_eq!(statistics.p50, 0.0);
+        assert_eq!(statistics.p95, 100.0);
+        assert_eq!(statistics.time_above_threshold, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn statistics_count_every_rise_above_the_threshold() {
+        let start = Instant::now();
+        let history = history_with(
+            start,
+            &[
+                (0, 60.0),
+                (1, 0.0),
+                (2, 50.0),
+                (3, 0.0),
+                (4, 50.0),
+                (5, 50.0),
+            ],
+            Duration::from_secs(120),
+        );
+        let now = start + Duration::from_secs(6);
+
+        // the first sample is already above the threshold and not a crossing
+        let statistics = history.statistics(now, 40.0).unwrap();
+        assert_eq!(statistics.crossings, 2);
+        assert_eq!(statistics.time_above_threshold, Duration::from_secs(4));
+    }
+
+    #[test]
+    fn statistics_need_a_duration() {
+        let start = Instant::now();
+        assert!(History::new(Duration::from_secs(120))
+            .statistics(start, 50.0)
+            .is_none());
+
+        let history = history_with(start, &[(0, 10.0)], Duration::from_secs(120));
+        assert!(history.statistics(start, 50.0).is_none());
+        assert!(history
+            .statistics(start + Duration::from_secs(1), 50.0)
+            .is_some());
+    }
+
+    #[test]
+    fn push_drops_buckets_older_than_the_window() {
+        let start = Instant::now();
+        let history = history_with(
+            start,
+            &[(0, 1.0), (5, 2.0), (10, 3.0), (11, 4.0)],
+            Duration::from_secs(10),
+        );
+
+        let values = history
+            .iter()
+            .map(|bucket| bucket.avg())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn compaction_merges_the_older_half() {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_secs(3600));
+        let count = HISTORY_CAPACITY + 1;
+        for i in 0..count {
+            history.push(start + Duration::from_millis(i as u64), i as f64);
+        }
+
+        let older_half = count / 2;
+        assert_eq!(history.len(), older_half / 2 + (count - older_half));
+
+        let first = history.iter().next().unwrap();
+        assert_eq!(first.start, start);
+        assert_eq!(first.end, start + Duration::from_millis(1));
+        assert_eq!(first.min, 0.0);
+        assert_eq!(first.max, 1.0);
+        assert_eq!(first.avg(), 0.5);
+
+        // the recent samples stay at full resolution
+        let last = history.iter().next_back().unwrap();
+        assert_eq!(last.start, last.end);
+        assert_eq!(last.avg(), HISTORY_CAPACITY as f64);
+    }
+
+    #[test]
+    fn compaction_keeps_the_history_bounded() {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_secs(3600));
+        for i in 0..10 * HISTORY_CAPACITY {
+            history.push(start + Duration::from_millis(i as u64), 1.0);
+        }
+
+        assert!(history.len() <= HISTORY_CAPACITY);
+        assert_eq!(history.iter().next().unwrap().start, start);
+        assert!(history.iter().all(|bucket| bucket.avg() == 1.0));
+    }
+
+    #[test]
+    fn buckets_are_ordered_and_do_not_overlap() {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_secs(3600));
+        for i in 0..3 * HISTORY_CAPACITY {
+            history.push(start + Duration::from_millis(i as u64), (i % 7) as f64);
+        }
+
+        let buckets = history.iter().collect::<Vec<_>>();
+        assert!(buckets.windows(2).all(|pair| pair[0].end < pair[1].start));
+    }
+
+    #[test]
+    fn step_points_hold_each_bucket_until_the_next_one() {
+        let start = Instant::now();
+        let history = history_with(start, &[(0, 10.0), (10, 20.0)], Duration::from_secs(60));
+        let now = start + Duration::from_secs(15);
+
+        let points = history.step_points(now, |bucket| bucket.avg());
+        assert_eq!(
+            points,
+            vec![(-15.0, 10.0), (-5.0, 10.0), (-5.0, 20.0), (0.0, 20.0)]
+        );
+    }
+}
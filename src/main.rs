@@ -2,18 +2,33 @@
 
 mod params;
 
+mod access;
+mod advisor;
 mod app;
+mod chart;
+mod config;
 mod event;
+mod history;
+mod proc_info;
+mod roudi_config;
+mod runtime;
+mod top;
+mod topology;
 mod types;
 mod ui;
+mod wait_for;
 
-use crate::app::App;
+use crate::app::{App, KioskState};
 use crate::event::{Config, Event, Events};
+use crate::history::LeakDetector;
+use crate::params::Command;
+use crate::roudi_config::RouDiConfig;
 
 use iceoryx_rs::Runtime;
 
 use structopt::StructOpt;
 
+use termion::event::Key;
 use termion::input::MouseTerminal;
 use termion::raw::IntoRawMode;
 use termion::screen::IntoAlternateScreen;
@@ -29,12 +44,38 @@ use std::error::Error;
 fn main() -> Result<(), Box<dyn Error>> {
     let params = params::Params::from_args();
 
+    if let Some(command) = params.command.as_ref() {
+        return run_command(command, &params);
+    }
+
+    let roudi_config = params
+        .roudi_config
+        .as_ref()
+        .map(|path| RouDiConfig::load(path))
+        .transpose()?;
+
+    let config = match params.config.as_ref() {
+        Some(path) => config::Config::load(path)?,
+        None => config::Config::default(),
+    };
+
+    if params.check_roudi_config {
+        if let Some(roudi_config) = roudi_config.as_ref() {
+            return check_roudi_config(roudi_config, &params);
+        }
+    }
+
     Runtime::init("iceray");
 
-    let events = Events::new(Config {
+    let mut event_config = Config {
         tick_rate: Duration::from_millis(params.update_interval),
         ..Config::default()
-    });
+    };
+    if params.kiosk {
+        // the exit key must not stop the input handling since it is ignored in kiosk mode
+        event_config.exit_key = Key::Null;
+    }
+    let events = Events::new(event_config);
 
     let stdout = io::stdout().into_raw_mode()?;
     let stdout = MouseTerminal::from(stdout);
@@ -42,8 +83,25 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
     terminal.hide_cursor()?;
 
-    let mut app = App::new("iceray - iceoryx Introspection");
+    let mut app = App::new("iceray - iceoryx Introspection", params.history);
     app.tabs.index = params.initial_page as usize;
+    app.roudi_config = roudi_config;
+    app.config = config;
+    app.memory.leak_detector = Some(LeakDetector {
+        // older samples are not kept
+        window: params
+            .leak_window
+            .unwrap_or(params.history)
+            .min(params.history),
+        min_growth: params.leak_min_growth,
+    });
+    if params.kiosk {
+        app.start_kiosk(KioskState::new(
+            params.kiosk_interval,
+            params.kiosk_mempools,
+            params.kiosk_unlock_key,
+        ));
+    }
     ui::draw(&mut terminal, &mut app)?;
 
     loop {
@@ -67,3 +125,75 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn check_roudi_config(
+    roudi_config: &RouDiConfig,
+    params: &params::Params,
+) -> Result<(), Box<dyn Error>> {
+    runtime::init_unique();
+
+    let poll_interval = Duration::from_millis(params.update_interval);
+    let mismatches = roudi_config::run_check(roudi_config, poll_interval)?;
+    if !mismatches.is_empty() {
+        eprintln!("The live segments do not match the RouDi config:");
+        for mismatch in mismatches {
+            match mismatch.segment_id {
+                Some(segment_id) => {
+                    eprintln!(" • Segment {}: {}", segment_id, mismatch.description)
+                }
+                None => eprintln!(" • {}", mismatch.description),
+            }
+        }
+        std::process::exit(1);
+    }
+
+    println!("The live segments match the RouDi config");
+    Ok(())
+}
+
+fn run_command(command: &Command, params: &params::Params) -> Result<(), Box<dyn Error>> {
+    runtime::init_unique();
+
+    let config = match params.config.as_ref() {
+        Some(path) => config::Config::load(path)?,
+        None => config::Config::default(),
+    };
+
+    match command {
+        Command::WaitFor(wait_for_params) => {
+            let poll_interval = Duration::from_millis(params.update_interval);
+            if let Err(unmet) = wait_for::run(wait_for_params, poll_interval) {
+                eprintln!(
+                    "Timeout after {:?} while waiting for:",
+                    wait_for_params.timeout
+                );
+                unmet
+                    .iter()
+                    .for_each(|condition| eprintln!(" • {}", condition));
+                std::process::exit(1);
+            }
+        }
+        Command::ExportTopology(export_params) => {
+            let poll_interval = Duration::from_millis(params.update_interval);
+            topology::export(export_params, poll_interval)?;
+        }
+        Command::AdviseMempools(advise_params) => {
+            let poll_interval = Duration::from_millis(params.update_interval);
+            advisor::run(advise_params, poll_interval, &config)?;
+        }
+        Command::ExportChart(chart_params) => {
+            let poll_interval = Duration::from_millis(params.update_interval);
+            chart::run(chart_params, poll_interval, &config)?;
+        }
+        Command::Top(top_params) => {
+            top::run(
+                top_params,
+                Duration::from_millis(params.update_interval),
+                params.history,
+                config,
+            )?;
+        }
+    }
+
+    Ok(())
+}
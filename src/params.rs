@@ -1,9 +1,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::Pages;
+use crate::topology::{Collapse, GraphFormat};
+use crate::types::{
+    parse_duration, parse_mempool, parse_non_negative, parse_nonzero_duration, parse_positive,
+    Pages, ServiceName,
+};
 
 use structopt::StructOpt;
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 /// iceray - iceoryx introspection
 #[derive(StructOpt, Debug)]
 #[structopt(name = "iceray")]
@@ -14,4 +21,123 @@ pub struct Params {
     /// The initial page to show on startup
     #[structopt(short, long, default_value = "Memory")]
     pub initial_page: Pages,
+    /// The time window of the mempool usage history, e.g. '10min' or '1h'
+    #[structopt(long, default_value = "2min", parse(try_from_str = parse_duration))]
+    pub history: Duration,
+    /// The time window in which a steadily growing mempool usage is reported as suspected leak;
+    /// the history window if not set
+    #[structopt(long, parse(try_from_str = parse_duration))]
+    pub leak_window: Option<Duration>,
+    /// The percentage points the lowest mempool usage has to grow within the leak window to be
+    /// reported; lower values are more sensitive
+    #[structopt(long, default_value = "5", parse(try_from_str = parse_positive))]
+    pub leak_min_growth: f64,
+    /// Rotates through the pages for wall displays; all keys except the unlock key are ignored
+    #[structopt(long)]
+    pub kiosk: bool,
+    /// The time each page is shown in kiosk mode, e.g. '10s' or '1min'
+    #[structopt(long, default_value = "10s", parse(try_from_str = parse_duration))]
+    pub kiosk_interval: Duration,
+    /// The number of fullest mempools the Memory page steps through in kiosk mode
+    #[structopt(long, default_value = "3")]
+    pub kiosk_mempools: usize,
+    /// The key to leave kiosk mode
+    #[structopt(long, default_value = "u")]
+    pub kiosk_unlock_key: char,
+    /// The iceray config file with e.g. the payload sizes of the services
+    #[structopt(short, long, parse(from_os_str))]
+    pub config: Option<PathBuf>,
+    /// The RouDi config file to compare the live segments and mempools with
+    #[structopt(long, parse(from_os_str))]
+    pub roudi_config: Option<PathBuf>,
+    /// Compares the live segments with the RouDi config and exits non-zero on mismatches
+    #[structopt(long, requires = "roudi-config")]
+    pub check_roudi_config: bool,
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Blocks until the given services and processes are available
+    WaitFor(WaitForParams),
+    /// Exports the process and service topology as Graphviz DOT or Mermaid graph
+    ExportTopology(ExportTopologyParams),
+    /// Shows a compact live summary below the prompt without taking over the screen
+    Top(TopParams),
+    /// Recommends a RouDi config with the mempool sizes derived from the observed peak usage
+    AdviseMempools(AdviseMemPoolsParams),
+    /// Records the mempool usage and exports it as SVG or PNG chart
+    ExportChart(ExportChartParams),
+}
+
+#[derive(StructOpt, Debug)]
+pub struct WaitForParams {
+    /// The service to wait for, given as 'service/instance/event'
+    #[structopt(long, number_of_values = 1)]
+    pub service: Vec<ServiceName>,
+    /// The minimal number of publisher ports each service needs
+    #[structopt(long, default_value = "0")]
+    pub publishers: usize,
+    /// The minimal number of subscriber ports each service needs
+    #[structopt(long, default_value = "0")]
+    pub subscribers: usize,
+    /// The name of a process which needs to be registered at RouDi
+    #[structopt(long, number_of_values = 1)]
+    pub process: Vec<String>,
+    /// The time to wait before giving up, e.g. '500ms', '30s' or '2min'
+    #[structopt(long, default_value = "30s", parse(try_from_str = parse_duration))]
+    pub timeout: Duration,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ExportTopologyParams {
+    /// The output format, either 'dot' or 'mermaid'
+    #[structopt(short, long, default_value = "dot")]
+    pub format: GraphFormat,
+    /// Merges services into one node, either 'none', 'service-id' or 'instance-id'
+    #[structopt(short, long, default_value = "none")]
+    pub collapse: Collapse,
+    /// The time to collect introspection data before the export
+    #[structopt(long, default_value = "2s", parse(try_from_str = parse_duration))]
+    pub sample_time: Duration,
+    /// The file to write the graph to; stdout if not set
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct TopParams {
+    /// The number of the fullest mempools to show
+    #[structopt(short, long, default_value = "3")]
+    pub mempools: usize,
+    /// Exits after the given time, e.g. '30s' or '5min'; runs until 'q' is pressed if not set
+    #[structopt(short, long, parse(try_from_str = parse_duration))]
+    pub duration: Option<Duration>,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct AdviseMemPoolsParams {
+    /// The time to observe the mempool usage, e.g. '10s' or '10min'
+    #[structopt(long, default_value = "10s", parse(try_from_str = parse_duration))]
+    pub sample_time: Duration,
+    /// The safety margin in percent which is added to the peak usage
+    #[structopt(short, long, default_value = "20", parse(try_from_str = parse_non_negative))]
+    pub margin: f64,
+    /// The file to write the RouDi config to; stdout if not set
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ExportChartParams {
+    /// The time to record the mempool usage, e.g. '30s' or '5min'; it is the width of the time axis
+    #[structopt(long, default_value = "1min", parse(try_from_str = parse_nonzero_duration))]
+    pub sample_time: Duration,
+    /// The mempool to show, given as 'segment/index'; all mempools if not set
+    #[structopt(short, long, number_of_values = 1, parse(try_from_str = parse_mempool))]
+    pub mempool: Vec<(u32, usize)>,
+    /// The file to write the chart to; a PNG if it ends with '.png' and an SVG otherwise
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: PathBuf,
 }
@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::access::{self, Credentials};
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// USER_HZ, the unit of the times in `/proc/<pid>/stat`; the kernel reports them in 1/100 s on all
+/// common architectures, independent of its internal tick rate
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// The resource usage and metadata of a process from `/proc/<pid>`
+pub struct ProcessInfo {
+    /// the arguments joined with spaces
+    pub cmdline: String,
+    pub uid: u32,
+    pub user: Option<String>,
+    /// the single letter state, e.g. 'R' for running or 'S' for sleeping
+    pub state: char,
+    pub threads: u64,
+    /// the resident set size in bytes
+    pub rss: u64,
+    pub start_time: SystemTime,
+    /// the time spent in user and kernel mode
+    pub cpu_time: Duration,
+}
+
+impl ProcessInfo {
+    pub fn uptime(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.start_time)
+            .unwrap_or_default()
+    }
+}
+
+/// The fields of `/proc/<pid>/stat` which are shown
+#[derive(Debug, PartialEq)]
+struct Stat {
+    state: char,
+    /// the time spent in user and kernel mode in clock ticks
+    cpu_ticks: u64,
+    threads: u64,
+    /// the start time in clock ticks after boot
+    start_ticks: u64,
+}
+
+impl Stat {
+    fn parse(stat: &str) -> Option<Self> {
+        // the command name in parentheses may contain spaces and parentheses, so the fields are
+        // counted after the last ')'; the first one is the state, i.e. field 3 in the proc(5)
+        // numbering
+        let (_, fields) = stat.rsplit_once(')')?;
+        let fields = fields.split_whitespace().collect::<Vec<_>>();
+        let field = |number: usize| -> Option<u64> { fields.get(number - 3)?.parse().ok() };
+
+        Some(Self {
+            state: fields.first()?.chars().next()?,
+            cpu_ticks: field(14)? + field(15)?,
+            threads: field(20)?,
+            start_ticks: field(22)?,
+        })
+    }
+}
+
+/// The data of a process which does not change while it runs
+struct Identity {
+    start_ticks: u64,
+    cmdline: String,
+    start_time: SystemTime,
+}
+
+/// Reads the `ProcessInfo` of the processes and caches what does not change between updates, so
+/// that an update only reads the stat and status of each process
+#[derive(Default)]
+pub struct ProcessInfoReader {
+    boot_time: Option<SystemTime>,
+    user_names: HashMap<u32, Option<String>>,
+    identities: HashMap<i32, Identity>,
+}
+
+impl ProcessInfoReader {
+    pub fn read(&mut self, pid: i32) -> io::Result<ProcessInfo> {
+        let invalid = |what: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("No {} for process {}", what, pid),
+            )
+        };
+
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid))?;
+        let stat = Stat::parse(&stat).ok_or_else(|| invalid("stat"))?;
+
+        let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+        // kernel threads have no 'VmRSS' line
+        let rss = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+            .map_or(0, |kib| kib * 1024);
+        let uid = Credentials::parse_status(&status)
+            .ok_or_else(|| invalid("uid"))?
+            .uid;
+
+        // a different start time means the pid was reused by another process
+        let known = matches!(
+            self.identities.get(&pid),
+            Some(identity) if identity.start_ticks == stat.start_ticks
+        );
+        if !known {
+            let identity = self.identity(pid, stat.start_ticks)?;
+            self.identities.insert(pid, identity);
+        }
+        let identity = &self.identities[&pid];
+
+        let user = self
+            .user_names
+            .entry(uid)
+            .or_insert_with(|| access::user_name(uid))
+            .clone();
+
+        Ok(ProcessInfo {
+            cmdline: identity.cmdline.clone(),
+            uid,
+            user,
+            state: stat.state,
+            threads: stat.threads,
+            rss,
+            start_time: identity.start_time,
+            cpu_time: ticks_to_duration(stat.cpu_ticks),
+        })
+    }
+
+    /// Drops the cached data of the processes which are gone
+    pub fn retain<F>(&mut self, is_alive: F)
+    where
+        F: Fn(i32) -> bool,
+    {
+        self.identities.retain(|pid, _| is_alive(*pid));
+    }
+
+    fn identity(&mut self, pid: i32, start_ticks: u64) -> io::Result<Identity> {
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid))?
+            .split(|&byte| byte == 0)
+            .filter(|argument| !argument.is_empty())
+            .map(|argument| String::from_utf8_lossy(argument).into_owned())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        // the boot time is read once, it only changes with a reboot
+        let boot_time = match self.boot_time {
+            Some(boot_time) => boot_time,
+            None => {
+                let boot_time = boot_time()?;
+                self.boot_time = Some(boot_time);
+                boot_time
+            }
+        };
+
+        Ok(Identity {
+            start_ticks,
+            cmdline,
+            start_time: boot_time + ticks_to_duration(start_ticks),
+        })
+    }
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SECOND)
+}
+
+/// The start times in `/proc/<pid>/stat` are relative to the boot time from `/proc/stat`
+fn boot_time() -> io::Result<SystemTime> {
+    fs::read_to_string("/proc/stat")?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime"))
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "No boot time in '/proc/stat'"))
+}
+
+pub fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "Running",
+        'S' => "Sleeping",
+        'D' => "Disk Sleep",
+        'Z' => "Zombie",
+        'T' => "Stopped",
+        't' => "Tracing Stop",
+        'X' | 'x' => "Dead",
+        'I' => "Idle",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stat line with the fields up to the start time and the trailing ones cut off
+    fn stat_line(comm: &str) -> String {
+        format!(
+            "4242 ({}) S 1 4242 4242 0 -1 4194560 1500 0 0 0 250 75 0 0 20 0 7 0 123456 1000",
+            comm
+        )
+    }
+
+    #[test]
+    fn stat_fields_after_the_command_name() {
+        assert_eq!(
+            Stat::parse(&stat_line("radar")),
+            Some(Stat {
+                state: 'S',
+                cpu_ticks: 325,
+                threads: 7,
+                start_ticks: 123456,
+            })
+        );
+    }
+
+    #[test]
+    fn stat_command_name_with_spaces_and_parentheses() {
+        let stat = Stat::parse(&stat_line("my (radar) node")).unwrap();
+        assert_eq!(stat.state, 'S');
+        assert_eq!(stat.threads, 7);
+        assert_eq!(stat.start_ticks, 123456);
+
+        let stat = Stat::parse(&stat_line(") R 1 2 3")).unwrap();
+        assert_eq!(stat.state, 'S');
+        assert_eq!(stat.cpu_ticks, 325);
+    }
+
+    #[test]
+    fn stat_rejects_truncated_input() {
+        assert_eq!(Stat::parse(""), None);
+        assert_eq!(Stat::parse("4242 (radar"), None);
+        assert_eq!(Stat::parse("4242 (radar) S 1 4242"), None);
+        assert_eq!(
+            Stat::parse("4242 (radar) S 1 4242 4242 0 -1 4194560 1500 0 0 0 x 75"),
+            None
+        );
+    }
+
+    #[test]
+    fn ticks_are_hundredths_of_a_second() {
+        assert_eq!(ticks_to_duration(250), Duration::from_millis(2500));
+    }
+}
@@ -0,0 +1,304 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::MemorySegments;
+use crate::runtime;
+
+use serde::Deserialize;
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// The time to wait for mempool introspection data in the headless check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The iceoryx RouDi config file with the shared memory segments and their mempools
+#[derive(Debug, Deserialize)]
+pub struct RouDiConfig {
+    #[serde(default)]
+    pub segment: Vec<SegmentConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SegmentConfig {
+    pub reader: Option<String>,
+    pub writer: Option<String>,
+    #[serde(default)]
+    pub mempool: Vec<MemPoolConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemPoolConfig {
+    /// the payload size of the chunks
+    pub size: u64,
+    pub count: u64,
+}
+
+/// The layout of a live segment as far as it is described by the config
+struct LiveSegment {
+    segment_id: u32,
+    writer: Option<String>,
+    reader: Option<String>,
+    mempools: Vec<LiveMemPool>,
+}
+
+struct LiveMemPool {
+    payload_size: u64,
+    chunks: u64,
+}
+
+/// A difference between the RouDi config and the live segments
+pub struct Mismatch {
+    pub segment_id: Option<u32>,
+    pub description: String,
+}
+
+impl RouDiConfig {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read RouDi config '{}': {}", path.display(), e))?;
+        let config = toml::from_str(&content)
+            .map_err(|e| format!("Could not parse RouDi config '{}': {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    /// Compares the segments of the config in order with the live segments
+    pub fn check(&self, memory: &MemorySegments) -> Vec<Mismatch> {
+        let sample = if let Some(sample) = memory.segments.as_ref() {
+            sample
+        } else {
+            return Vec::new();
+        };
+
+        let live_segments = sample
+            .memory_segments()
+            .into_iter()
+            .map(|segment| LiveSegment {
+                segment_id: segment.segment_id(),
+                writer: segment.writer_group(),
+                reader: segment.reader_group(),
+                mempools: segment
+                    .mempools()
+                    .into_iter()
+                    .map(|mempool| LiveMemPool {
+                        payload_size: mempool.payload_size as u64,
+                        chunks: mempool.total_number_of_chunks as u64,
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        self.compare(&live_segments)
+    }
+
+    fn compare(&self, live_segments: &[LiveSegment]) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+
+        if live_segments.len() != self.segment.len() {
+            mismatches.push(Mismatch {
+                segment_id: None,
+                description: format!(
+                    "{} segments but the config has {}",
+                    live_segments.len(),
+                    self.segment.len()
+                ),
+            });
+        }
+
+        for (segment, segment_config) in live_segments.iter().zip(self.segment.iter()) {
+            let segment_id = Some(segment.segment_id);
+            let mut mismatch = |description: String| {
+                mismatches.push(Mismatch {
+                    segment_id,
+                    description,
+                })
+            };
+
+            // without groups in the config RouDi uses its own group, which is not known here
+            let groups = [
+                ("writer", &segment.writer, segment_config.writer.as_ref()),
+                ("reader", &segment.reader, segment_config.reader.as_ref()),
+            ];
+            for (kind, live, configured) in groups.iter() {
+                if let Some(configured) = configured {
+                    if live.as_ref() != Some(configured) {
+                        mismatch(format!(
+                            "{} group '{}' but the config has '{}'",
+                            kind,
+                            live.as_deref().unwrap_or("##Error##"),
+                            configured
+                        ));
+                    }
+                }
+            }
+
+            if segment.mempools.len() != segment_config.mempool.len() {
+                mismatch(format!(
+                    "{} mempools but the config has {}",
+                    segment.mempools.len(),
+                    segment_config.mempool.len()
+                ));
+            }
+
+            for (index, (mempool, mempool_config)) in segment
+                .mempools
+                .iter()
+                .zip(segment_config.mempool.iter())
+                .enumerate()
+            {
+                if mempool.payload_size != mempool_config.size {
+                    mismatch(format!(
+                        "MemPool {} has a payload size of {} but the config has {}",
+                        index, mempool.payload_size, mempool_config.size
+                    ));
+                }
+                if mempool.chunks != mempool_config.count {
+                    mismatch(format!(
+                        "MemPool {} has {} chunks but the config has {}",
+                        index, mempool.chunks, mempool_config.count
+                    ));
+                }
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// Waits for the mempool introspection and compares it with the config; returns the mismatches
+pub fn run_check(
+    config: &RouDiConfig,
+    poll_interval: Duration,
+) -> Result<Vec<Mismatch>, Box<dyn Error>> {
+    let mut memory = MemorySegments::new(CHECK_TIMEOUT);
+
+    let received = runtime::sample_for(CHECK_TIMEOUT, poll_interval, || {
+        memory.update();
+        memory.segments.is_some()
+    });
+    if !received {
+        return Err("No mempool introspection data received!".into());
+    }
+
+    Ok(config.check(&memory))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[[segment]]
+reader = "iox_roudi_test1"
+writer = "iox_roudi_test2"
+
+[[segment.mempool]]
+size = 128
+count = 100
+
+[[segment.mempool]]
+size = 1024
+count = 20
+
+[[segment]]
+
+[[segment.mempool]]
+size = 64
+count = 10
+"#;
+
+    fn live_segments() -> Vec<LiveSegment> {
+        vec![
+            LiveSegment {
+                segment_id: 1,
+                writer: Some("iox_roudi_test2".to_string()),
+                reader: Some("iox_roudi_test1".to_string()),
+                mempools: vec![
+                    LiveMemPool {
+                        payload_size: 128,
+                        chunks: 100,
+                    },
+                    LiveMemPool {
+                        payload_size: 1024,
+                        chunks: 20,
+                    },
+                ],
+            },
+            LiveSegment {
+                segment_id: 2,
+                writer: Some("roudi".to_string()),
+                reader: Some("roudi".to_string()),
+                mempools: vec![LiveMemPool {
+                    payload_size: 64,
+                    chunks: 10,
+                }],
+            },
+        ]
+    }
+
+    fn config() -> RouDiConfig {
+        toml::from_str(CONFIG).unwrap()
+    }
+
+    #[test]
+    fn matching_segments_have_no_mismatch() {
+        // the groups of the second segment are not configured and therefore not compared
+        assert!(config().compare(&live_segments()).is_empty());
+    }
+
+    #[test]
+    fn different_segment_count_is_reported() {
+        let mut segments = live_segments();
+        segments.pop();
+
+        let mismatches = config().compare(&segments);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].segment_id, None);
+    }
+
+    #[test]
+    fn different_groups_are_reported() {
+        let mut segments = live_segments();
+        segments[0].writer = Some("other".to_string());
+        segments[0].reader = None;
+
+        let mismatches = config().compare(&segments);
+        assert_eq!(mismatches.len(), 2);
+        assert!(mismatches.iter().all(|m| m.segment_id == Some(1)));
+        assert!(mismatches[0]
+            .description
+            .starts_with("writer group 'other'"));
+        assert!(mismatches[1]
+            .description
+            .starts_with("reader group '##Error##'"));
+    }
+
+    #[test]
+    fn different_mempools_are_reported() {
+        let mut segments = live_segments();
+        segments[0].mempools[1].payload_size = 2048;
+        segments[0].mempools[1].chunks = 10;
+        segments[1].mempools.push(LiveMemPool {
+            payload_size: 256,
+            chunks: 1,
+        });
+
+        let mismatches = config().compare(&segments);
+        let descriptions = mismatches
+            .iter()
+            .map(|m| (m.segment_id, m.description.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            vec![
+                (
+                    Some(1),
+                    "MemPool 1 has a payload size of 2048 but the config has 1024"
+                ),
+                (Some(1), "MemPool 1 has 10 chunks but the config has 20"),
+                (Some(2), "2 mempools but the config has 1"),
+            ]
+        );
+    }
+}
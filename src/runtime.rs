@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use iceoryx_rs::Runtime;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Registers at RouDi with a name containing the pid, which allows to run the commands in parallel
+/// to the TUI
+pub fn init_unique() {
+    Runtime::init(&format!("iceray-{}", std::process::id()));
+}
+
+/// Calls `poll` every `poll_interval` until it returns true or `duration` elapsed; `poll` is called
+/// at least once and once more at the deadline. Returns whether `poll` succeeded.
+pub fn sample_for<F>(duration: Duration, poll_interval: Duration, mut poll: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    // a deadline beyond the range of `Instant` is never reached
+    let deadline = Instant::now().checked_add(duration);
+    loop {
+        if poll() {
+            return true;
+        }
+
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => poll_interval,
+        };
+        if remaining == Duration::from_secs(0) {
+            return false;
+        }
+        thread::sleep(poll_interval.min(remaining));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_for_stops_when_poll_succeeds() {
+        let mut calls = 0;
+        let succeeded = sample_for(Duration::from_secs(10), Duration::from_millis(1), || {
+            calls += 1;
+            calls == 3
+        });
+        assert!(succeeded);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn sample_for_polls_once_without_time() {
+        let mut calls = 0;
+        let succeeded = sample_for(Duration::from_secs(0), Duration::from_millis(1), || {
+            calls += 1;
+            false
+        });
+        assert!(!succeeded);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn sample_for_polls_at_the_deadline() {
+        let start = Instant::now();
+        let mut calls = 0;
+        sample_for(Duration::from_millis(20), Duration::from_millis(50), || {
+            calls += 1;
+            false
+        });
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn sample_for_accepts_huge_durations() {
+        assert!(sample_for(
+            Duration::from_secs(u64::MAX),
+            Duration::from_millis(1),
+            || true
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::App;
+use crate::config;
+use crate::event::{Config, Event, Events};
+use crate::params::TopParams;
+use crate::ui;
+
+use termion::event::Key;
+use termion::raw::IntoRawMode;
+
+use ratatui::backend::TermionBackend;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Redraws a compact summary in place below the prompt; without a terminal, e.g. in a CI log,
+/// the summary is printed as plain text on every update
+pub fn run(
+    params: &TopParams,
+    update_interval: Duration,
+    history_window: Duration,
+    config: config::Config,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new("iceray - top", history_window);
+    app.config = config;
+
+    if !termion::is_tty(&io::stdout()) {
+        return run_plain(params, update_interval, &mut app);
+    }
+
+    let events = Events::new(Config {
+        tick_rate: update_interval,
+        ..Config::default()
+    });
+
+    let stdout = io::stdout().into_raw_mode()?;
+    let backend = TermionBackend::new(stdout);
+    let mut terminal = Terminal::with_options(
+        backend,
+        TerminalOptions {
+            viewport: Viewport::Inline(ui::top::height(params.mempools)),
+        },
+    )?;
+
+    let start = Instant::now();
+    loop {
+        terminal.draw(|frame| {
+            let area = frame.size();
+            ui::top::draw(frame, area, &app, params.mempools)
+        })?;
+
+        match events.next()? {
+            Event::Input(Key::Char('q'))
+            | Event::Input(Key::Ctrl('c'))
+            | Event::Input(Key::Esc) => break,
+            Event::Tick => app.on_tick(),
+            _ => {}
+        }
+
+        if matches!(params.duration, Some(duration) if start.elapsed() >= duration) {
+            break;
+        }
+    }
+
+    // leave the last frame in the scrollback and continue below it
+    let area = terminal.get_frame().size();
+    terminal.set_cursor(0, area.bottom().saturating_sub(1))?;
+    terminal.show_cursor()?;
+    write!(terminal.backend_mut(), "\r\n")?;
+    terminal.backend_mut().flush()?;
+
+    Ok(())
+}
+
+fn run_plain(
+    params: &TopParams,
+    update_interval: Duration,
+    app: &mut App,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    loop {
+        app.on_tick();
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        for line in ui::top::summary(app, params.mempools) {
+            let text = line
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>();
+            writeln!(stdout, "{}", text)?;
+        }
+        writeln!(stdout)?;
+
+        if matches!(params.duration, Some(duration) if start.elapsed() >= duration) {
+            return Ok(());
+        }
+        thread::sleep(update_interval);
+    }
+}
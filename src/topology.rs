@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::{ProcessList, ServiceList};
+use crate::params::ExportTopologyParams;
+use crate::runtime;
+
+use iceoryx_rs::introspection::ServiceDescription;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::error::Error;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collapse {
+    None,
+    ServiceId,
+    InstanceId,
+}
+
+impl FromStr for Collapse {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "none" => Ok(Collapse::None),
+            "service-id" => Ok(Collapse::ServiceId),
+            "instance-id" => Ok(Collapse::InstanceId),
+            _ => Err("Could not parse collapse mode!".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            _ => Err("Could not parse graph format!".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TopologyNode {
+    Process(String),
+    Service(String),
+}
+
+impl TopologyNode {
+    pub fn label(&self) -> &str {
+        match self {
+            TopologyNode::Process(name) => name,
+            TopologyNode::Service(name) => name,
+        }
+    }
+}
+
+/// The bipartite graph of processes and services; the edges are the ports connecting them
+pub struct Topology {
+    pub processes: BTreeSet<String>,
+    pub services: BTreeSet<String>,
+    /// number of publisher ports from a process to a service
+    pub publishers: BTreeMap<(String, String), usize>,
+    /// number of subscriber ports from a service to a process
+    pub subscribers: BTreeMap<(String, String), usize>,
+}
+
+impl Topology {
+    pub fn new(processes: &ProcessList, services: &ServiceList, collapse: Collapse) -> Self {
+        let mut topology = Topology {
+            processes: processes.map.keys().cloned().collect(),
+            services: BTreeSet::new(),
+            publishers: BTreeMap::new(),
+            subscribers: BTreeMap::new(),
+        };
+
+        for (service, details) in services.map.iter() {
+            let service = service_label(service, collapse);
+            for process in details.publisher_processes.iter() {
+                topology.processes.insert(process.clone());
+                *topology
+                    .publishers
+                    .entry((process.clone(), service.clone()))
+                    .or_insert(0) += 1;
+            }
+            for process in details.subscriber_processes.iter() {
+                topology.processes.insert(process.clone());
+                *topology
+                    .subscribers
+                    .entry((service.clone(), process.clone()))
+                    .or_insert(0) += 1;
+            }
+            topology.services.insert(service);
+        }
+
+        topology
+    }
+
+    /// Splits the graph into the layers publishing processes, services and subscribing processes;
+    /// processes with publisher and subscriber ports are part of both process layers
+    pub fn layers(&self) -> [Vec<TopologyNode>; 3] {
+        let publishing: BTreeSet<&String> =
+            self.publishers.keys().map(|(process, _)| process).collect();
+        let subscribing: BTreeSet<&String> = self
+            .subscribers
+            .keys()
+            .map(|(_, process)| process)
+            .collect();
+
+        let left = self
+            .processes
+            .iter()
+            .filter(|process| publishing.contains(process) || !subscribing.contains(process))
+            .cloned()
+            .map(TopologyNode::Process)
+            .collect();
+        let middle = self
+            .services
+            .iter()
+            .cloned()
+            .map(TopologyNode::Service)
+            .collect();
+        let right = self
+            .processes
+            .iter()
+            .filter(|process| subscribing.contains(process))
+            .cloned()
+            .map(TopologyNode::Process)
+            .collect();
+
+        [left, middle, right]
+    }
+
+    fn neighbours(&self, node: &TopologyNode) -> Vec<TopologyNode> {
+        match node {
+            TopologyNode::Process(name) => self
+                .publishers
+                .keys()
+                .filter(|(process, _)| process == name)
+                .map(|(_, service)| TopologyNode::Service(service.clone()))
+                .chain(
+                    self.subscribers
+                        .keys()
+                        .filter(|(_, process)| process == name)
+                        .map(|(service, _)| TopologyNode::Service(service.clone())),
+                )
+                .collect(),
+            TopologyNode::Service(name) => self
+                .publishers
+                .keys()
+                .filter(|(_, service)| service == name)
+                .map(|(process, _)| TopologyNode::Process(process.clone()))
+                .chain(
+                    self.subscribers
+                        .keys()
+                        .filter(|(service, _)| service == name)
+                        .map(|(_, process)| TopologyNode::Process(process.clone())),
+                )
+                .collect(),
+        }
+    }
+
+    /// The sub graph with all nodes which are at most `hops` edges away from `node`
+    pub fn neighbourhood(&self, node: &TopologyNode, hops: usize) -> Topology {
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(node.clone());
+        queue.push_back((node.clone(), 0));
+
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance >= hops {
+                continue;
+            }
+            for neighbour in self.neighbours(&current) {
+                if visited.insert(neighbour.clone()) {
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        let contains_process =
+            |process: &String| visited.contains(&TopologyNode::Process(process.clone()));
+        let contains_service =
+            |service: &String| visited.contains(&TopologyNode::Service(service.clone()));
+
+        Topology {
+            processes: self
+                .processes
+                .iter()
+                .filter(|process| contains_process(process))
+                .cloned()
+                .collect(),
+            services: self
+                .services
+                .iter()
+                .filter(|service| contains_service(service))
+                .cloned()
+                .collect(),
+            publishers: self
+                .publishers
+                .iter()
+                .filter(|((process, service), _)| {
+                    contains_process(process) && contains_service(service)
+                })
+                .map(|(edge, count)| (edge.clone(), *count))
+                .collect(),
+            subscribers: self
+                .subscribers
+                .iter()
+                .filter(|((service, process), _)| {
+                    contains_service(service) && contains_process(process)
+                })
+                .map(|(edge, count)| (edge.clone(), *count))
+                .collect(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph iceoryx {\n");
+        dot.push_str("    rankdir=LR;\n");
+
+        for process in self.processes.iter() {
+            dot.push_str(&format!(
+                "    \"p:{0}\" [label=\"{0}\", shape=box];\n",
+                escape_dot(process)
+            ));
+        }
+        for service in self.services.iter() {
+            dot.push_str(&format!(
+                "    \"s:{0}\" [label=\"{0}\", shape=ellipse];\n",
+                escape_dot(service)
+            ));
+        }
+
+        let edge = |from: &str, to: &str, count: usize| {
+            if count > 1 {
+                format!("    \"{}\" -> \"{}\" [label=\"{}\"];\n", from, to, count)
+            } else {
+                format!("    \"{}\" -> \"{}\";\n", from, to)
+            }
+        };
+        for ((process, service), count) in self.publishers.iter() {
+            dot.push_str(&edge(
+                &format!("p:{}", escape_dot(process)),
+                &format!("s:{}", escape_dot(service)),
+                *count,
+            ));
+        }
+        for ((service, process), count) in self.subscribers.iter() {
+            dot.push_str(&edge(
+                &format!("s:{}", escape_dot(service)),
+                &format!("p:{}", escape_dot(process)),
+                *count,
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        // mermaid ids must not contain arbitrary characters, therefore the nodes are enumerated
+        let process_ids: BTreeMap<&String, String> = self
+            .processes
+            .iter()
+            .enumerate()
+            .map(|(index, process)| (process, format!("p{}", index)))
+            .collect();
+        let service_ids: BTreeMap<&String, String> = self
+            .services
+            .iter()
+            .enumerate()
+            .map(|(index, service)| (service, format!("s{}", index)))
+            .collect();
+
+        let mut mermaid = String::new();
+        mermaid.push_str("flowchart LR\n");
+
+        for (process, id) in process_ids.iter() {
+            mermaid.push_str(&format!("    {}[\"{}\"]\n", id, escape_mermaid(process)));
+        }
+        for (service, id) in service_ids.iter() {
+            mermaid.push_str(&format!("    {}([\"{}\"])\n", id, escape_mermaid(service)));
+        }
+
+        let edge = |from: &str, to: &str, count: usize| {
+            if count > 1 {
+                format!("    {} -->|{}| {}\n", from, count, to)
+            } else {
+                format!("    {} --> {}\n", from, to)
+            }
+        };
+        for ((process, service), count) in self.publishers.iter() {
+            mermaid.push_str(&edge(&process_ids[process], &service_ids[service], *count));
+        }
+        for ((service, process), count) in self.subscribers.iter() {
+            mermaid.push_str(&edge(&service_ids[service], &process_ids[process], *count));
+        }
+
+        mermaid
+    }
+}
+
+pub fn service_label(service: &ServiceDescription, collapse: Collapse) -> String {
+    match collapse {
+        Collapse::None => format!(
+            "{} • {} • {}",
+            service.service_id, service.instance_id, service.event_id
+        ),
+        Collapse::ServiceId => service.service_id.clone(),
+        Collapse::InstanceId => format!("{} • {}", service.service_id, service.instance_id),
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+/// Samples the process and port introspection for the configured time and writes the topology
+pub fn export(
+    params: &ExportTopologyParams,
+    poll_interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut processes = ProcessList::new();
+    let mut services = ServiceList::new();
+
+    runtime::sample_for(params.sample_time, poll_interval, || {
+        processes.update();
+        services.update(&mut processes);
+        false
+    });
+
+    let topology = Topology::new(&processes, &services, params.collapse);
+    let graph = match params.format {
+        GraphFormat::Dot => topology.to_dot(),
+        GraphFormat::Mermaid => topology.to_mermaid(),
+    };
+
+    match params.output.as_ref() {
+        Some(path) => fs::write(path, graph)?,
+        None => print!("{}", graph),
+    }
+
+    Ok(())
+}
@@ -1,6 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use iceoryx_rs::introspection::ServiceDescription;
+
+use serde::{Deserialize, Deserializer};
+
+use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Pages {
@@ -8,6 +14,8 @@ pub enum Pages {
     Memory,
     Processes,
     Services,
+    Topology,
+    Heatmap,
 }
 
 impl FromStr for Pages {
@@ -19,7 +27,282 @@ impl FromStr for Pages {
             "Memory" => Ok(Pages::Memory),
             "Processes" => Ok(Pages::Processes),
             "Services" => Ok(Pages::Services),
+            "Topology" => Ok(Pages::Topology),
+            "Heatmap" => Ok(Pages::Heatmap),
             _ => Err("Could not parse page type!".to_string()),
         }
     }
 }
+
+/// A service given as `service_id/instance_id/event_id` on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceName {
+    pub service_id: String,
+    pub instance_id: String,
+    pub event_id: String,
+}
+
+impl ServiceName {
+    pub fn matches(&self, service: &ServiceDescription) -> bool {
+        self.service_id == service.service_id
+            && self.instance_id == service.instance_id
+            && self.event_id == service.event_id
+    }
+}
+
+impl FromStr for ServiceName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ids: Vec<&str> = s.trim().split('/').collect();
+        match ids.as_slice() {
+            [service_id, instance_id, event_id]
+                if !service_id.is_empty() && !instance_id.is_empty() && !event_id.is_empty() =>
+            {
+                Ok(ServiceName {
+                    service_id: service_id.to_string(),
+                    instance_id: instance_id.to_string(),
+                    event_id: event_id.to_string(),
+                })
+            }
+            _ => Err(format!(
+                "Could not parse service '{}'! Expected 'service/instance/event'",
+                s
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ServiceName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for ServiceName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            self.service_id, self.instance_id, self.event_id
+        )
+    }
+}
+
+/// Matches a text against a pattern with `*` for any number of characters and `?` for a single one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // the positions to continue from when a `*` has to match more characters
+    let mut backtrack = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Parses a mempool given as `segment/index`, e.g. `1/0` for the first mempool of segment 1
+pub fn parse_mempool(s: &str) -> Result<(u32, usize), String> {
+    let invalid = || format!("Invalid mempool '{}'; expected 'segment/index'", s);
+    let (segment_id, index) = s.trim().split_once('/').ok_or_else(invalid)?;
+    Ok((
+        segment_id.parse().map_err(|_| invalid())?,
+        index.parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// Parses a finite number which is not negative, e.g. a safety margin in percent
+pub fn parse_non_negative(s: &str) -> Result<f64, String> {
+    let value = s
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("Could not parse number '{}'!", s))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("'{}' is not a finite number of at least 0!", s));
+    }
+    Ok(value)
+}
+
+/// Parses a finite number which is larger than zero, e.g. a minimal growth
+pub fn parse_positive(s: &str) -> Result<f64, String> {
+    match parse_non_negative(s) {
+        Ok(value) if value > 0.0 => Ok(value),
+        _ => Err(format!("'{}' is not a finite number larger than 0!", s)),
+    }
+}
+
+/// The longest accepted duration, about 136 years
+const MAX_DURATION_SECONDS: f64 = u32::MAX as f64;
+
+/// Parses a duration like `500ms`, `30s`, `10min` or `1h`; a plain number is taken as seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(unit_start);
+
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("Could not parse duration '{}'!", s))?;
+    let seconds = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" | "min" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(format!("Unknown duration unit in '{}'!", s)),
+    };
+
+    // larger values would overflow the duration or the deadlines derived from it
+    if !(0.0..=MAX_DURATION_SECONDS).contains(&seconds) {
+        return Err(format!("Duration '{}' is out of range!", s));
+    }
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Parses a duration like `parse_duration` but rejects zero, e.g. for a time axis
+pub fn parse_nonzero_duration(s: &str) -> Result<Duration, String> {
+    match parse_duration(s)? {
+        duration if duration == Duration::from_secs(0) => {
+            Err(format!("Duration '{}' must not be zero!", s.trim()))
+        }
+        duration => Ok(duration),
+    }
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `512 B`, `1.5 KiB` or `20.0 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_with_wildcards() {
+        assert!(glob_match("1/128", "1/128"));
+        assert!(glob_match("*/128", "12/128"));
+        assert!(glob_match("1/*", "1/"));
+        assert!(glob_match("?/1*8", "1/1048"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("**/*", "1/128"));
+    }
+
+    #[test]
+    fn glob_match_without_match() {
+        assert!(!glob_match("1/128", "1/1280"));
+        assert!(!glob_match("*/128", "1/256"));
+        assert!(!glob_match("?/128", "12/128"));
+        assert!(!glob_match("?", ""));
+        assert!(!glob_match("", "1"));
+    }
+
+    #[test]
+    fn parse_duration_with_units() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1.5min"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration(" 1h "), Ok(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_input() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("1.2.3s").is_err());
+        assert!(parse_duration("10d").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflowing_values() {
+        assert!(parse_duration("99999999999999999999999").is_err());
+        assert!(parse_duration("99999999999999999999999h").is_err());
+        assert!(parse_duration(&"9".repeat(400)).is_err());
+    }
+
+    #[test]
+    fn parse_mempool_of_segment_and_index() {
+        assert_eq!(parse_mempool("1/0"), Ok((1, 0)));
+        assert_eq!(parse_mempool(" 12/3 "), Ok((12, 3)));
+    }
+
+    #[test]
+    fn parse_mempool_rejects_invalid_input() {
+        assert!(parse_mempool("").is_err());
+        assert!(parse_mempool("1").is_err());
+        assert!(parse_mempool("1/").is_err());
+        assert!(parse_mempool("/0").is_err());
+        assert!(parse_mempool("1/0/2").is_err());
+        assert!(parse_mempool("-1/0").is_err());
+        assert!(parse_mempool("a/b").is_err());
+    }
+
+    #[test]
+    fn parse_non_negative_numbers() {
+        assert_eq!(parse_non_negative("0"), Ok(0.0));
+        assert_eq!(parse_non_negative(" 12.5 "), Ok(12.5));
+        assert!(parse_non_negative("-1").is_err());
+        assert!(parse_non_negative("NaN").is_err());
+        assert!(parse_non_negative("inf").is_err());
+        assert!(parse_non_negative("ten").is_err());
+    }
+
+    #[test]
+    fn parse_positive_numbers() {
+        assert_eq!(parse_positive("0.5"), Ok(0.5));
+        assert!(parse_positive("0").is_err());
+        assert!(parse_positive("-0.5").is_err());
+        assert!(parse_positive("NaN").is_err());
+        assert!(parse_positive("inf").is_err());
+    }
+
+    #[test]
+    fn parse_nonzero_duration_rejects_zero() {
+        assert_eq!(parse_nonzero_duration("1ms"), Ok(Duration::from_millis(1)));
+        assert!(parse_nonzero_duration("0s").is_err());
+        assert!(parse_nonzero_duration("0.0min").is_err());
+        assert!(parse_nonzero_duration("x").is_err());
+    }
+}
@@ -1,19 +1,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
+mod heatmap;
 mod memory;
+mod overview;
 mod process;
 mod service;
+pub mod top;
+mod topology;
 
+use crate::config::Level;
 use crate::App;
 
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Tabs, Wrap};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Tabs};
 use ratatui::{Frame, Terminal};
 
 use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The time a status message is shown in the tab bar
+const MESSAGE_DURATION: Duration = Duration::from_secs(5);
 
 pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(), io::Error> {
     terminal.draw(|mut frame| {
@@ -28,16 +37,75 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(),
     Ok(())
 }
 
+/// Highlights a value which reached a usage threshold
+pub fn level_style(level: Level, style: Style) -> Style {
+    match level {
+        Level::Normal => style,
+        Level::Warning => Style::default().fg(Color::Yellow),
+        Level::Critical => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    }
+}
+
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    if seconds < 60 {
+        format!("{:.1}s", age.as_secs_f64())
+    } else if seconds < 3600 {
+        format!("{}min {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {}min", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
+
+/// Formats the time of day in UTC with milliseconds to correlate events with log files
+pub fn format_wall_clock(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03} UTC",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Formats a duration compactly for axis labels, e.g. `30s`, `2min` or `1.5h`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 60.0 {
+        format!("{:.0}s", seconds)
+    } else if seconds < 3600.0 {
+        format!("{:.3}", seconds / 60.0)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+            + "min"
+    } else {
+        format!("{:.3}", seconds / 3600.0)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+            + "h"
+    }
+}
+
 fn draw_tabbar<B>(frame: &mut Frame<B>, area: Rect, app: &App)
 where
     B: Backend,
 {
+    let title = match (app.kiosk.as_ref(), app.message.as_ref()) {
+        (Some(kiosk), _) => format!(
+            "Introspection Topics [Kiosk Mode - press '{}' to unlock]",
+            kiosk.unlock_key
+        ),
+        (None, Some((issued, message))) if issued.elapsed() < MESSAGE_DURATION => {
+            format!("Introspection Topics [{}]", message)
+        }
+        _ => "Introspection Topics".to_string(),
+    };
     let tabs = Tabs::new(app.tabs.titles.iter().cloned().map(Line::from).collect())
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Introspection Topics"),
-        )
+        .block(Block::default().borders(Borders::ALL).title(title))
         .select(app.tabs.index)
         .style(Style::default())
         .highlight_style(Style::default().fg(Color::Yellow));
@@ -50,24 +118,12 @@ where
     B: Backend,
 {
     match app.tabs.index {
-        0 => {
-            let mut text = Vec::<Line>::new();
-
-            text.push(Line::from(vec![Span::raw("unimplemented!")]));
-            text.push(Line::from(vec![Span::raw("")]));
-            text.push(Line::from(vec![Span::raw(
-                "use arrow keys to navigate to the next page!",
-            )]));
-
-            let paragraph = Paragraph::new(text)
-                .block(Block::default().borders(Borders::ALL).title("Overview"))
-                .wrap(Wrap { trim: false });
-
-            frame.render_widget(paragraph, area);
-        }
+        0 => overview::draw(frame, area, app),
         1 => memory::draw(frame, area, app),
         2 => process::draw(frame, area, app),
         3 => service::draw(frame, area, app),
+        4 => topology::draw(frame, area, app),
+        5 => heatmap::draw(frame, area, app),
         _ => {}
     }
 }
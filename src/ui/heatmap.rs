@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::App;
+use crate::history::History;
+use crate::ui::format_duration;
+
+use ratatui::backend::Backend;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+use std::time::{Duration, Instant};
+
+const LABEL_WIDTH: usize = 22;
+
+/// The upper usage limit in percent of each color
+const HEAT_COLORS: [(f64, Color); 5] = [
+    (25.0, Color::Blue),
+    (50.0, Color::Green),
+    (75.0, Color::Yellow),
+    (95.0, Color::LightRed),
+    (f64::INFINITY, Color::Red),
+];
+
+fn heat_color(usage: f64) -> Color {
+    HEAT_COLORS
+        .iter()
+        .find(|(limit, _)| usage < *limit)
+        .map_or(Color::Red, |(_, color)| *color)
+}
+
+/// The peak usage of each column, with the columns covering the window up to now; columns without a
+/// sample keep the value of the previous one since the usage is only sampled at the update interval
+fn column_values(
+    history: &History,
+    now: Instant,
+    window: Duration,
+    count: usize,
+) -> Vec<Option<f64>> {
+    let mut values = vec![None; count];
+
+    for bucket in history.iter() {
+        let age = now.saturating_duration_since(bucket.end).as_secs_f64() / window.as_secs_f64();
+        if age > 1.0 {
+            continue;
+        }
+        let column = count - 1 - ((age * count as f64) as usize).min(count - 1);
+        values[column] =
+            Some(values[column].map_or(bucket.max, |value: f64| value.max(bucket.max)));
+    }
+
+    let mut previous = None;
+    for value in values.iter_mut() {
+        if value.is_none() {
+            *value = previous;
+        }
+        previous = *value;
+    }
+
+    values
+}
+
+fn time_axis(window: Duration, count: usize) -> String {
+    let mut axis = vec![' '; count];
+    let mut print = |x: usize, label: &str| {
+        for (offset, c) in label.chars().enumerate() {
+            if let Some(cell) = axis.get_mut(x + offset) {
+                *cell = c;
+            }
+        }
+    };
+
+    let middle = format!("-{}", format_duration(window / 2));
+    print(0, &format!("-{}", format_duration(window)));
+    print((count / 2).saturating_sub(middle.len() / 2), &middle);
+    print(count.saturating_sub(3), "now");
+
+    axis.into_iter().collect()
+}
+
+pub fn draw<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("MemPool Usage over Time (Enter: Show Graph)");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let sample = if let Some(sample) = app.memory.segments.as_ref() {
+        sample
+    } else {
+        return;
+    };
+
+    if inner.width as usize <= LABEL_WIDTH || inner.height < 3 {
+        return;
+    }
+
+    let column_count = inner.width as usize - LABEL_WIDTH;
+    let now = Instant::now();
+    let window = app.memory.history_window;
+
+    let mut rows = Vec::<Line>::new();
+    let mut selected_row = 0;
+    sample.memory_segments().into_iter().for_each(|segment| {
+        let segment_id = segment.segment_id();
+        for (index, _) in segment.mempools().into_iter().enumerate() {
+            let style = if app.memory.selection == (segment_id, index) {
+                selected_row = rows.len();
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+
+            let values = app
+                .memory
+                .mempool_history
+                .get(&(segment_id, index))
+                .map(|history| column_values(&history.used_chunks, now, window, column_count))
+                .unwrap_or_else(|| vec![None; column_count]);
+
+            let mut spans = vec![Span::styled(
+                format!(
+                    "{:<width$}",
+                    format!(" Segment {} MemPool {}", segment_id, index),
+                    width = LABEL_WIDTH
+                ),
+                style,
+            )];
+            spans.extend(values.into_iter().map(|value| match value {
+                Some(usage) => Span::styled("█", Style::default().fg(heat_color(usage))),
+                None => Span::raw(" "),
+            }));
+            rows.push(Line::from(spans));
+        }
+    });
+
+    let mut legend = vec![Span::raw(format!("{:<width$}", "", width = LABEL_WIDTH))];
+    let mut lower_limit = 0.0;
+    for (limit, color) in HEAT_COLORS.iter() {
+        legend.push(Span::styled("█", Style::default().fg(*color)));
+        legend.push(Span::raw(format!(" ≥{}%  ", lower_limit)));
+        lower_limit = *limit;
+    }
+
+    // the time axis and the legend stay visible while the selected row is scrolled into view
+    let visible_rows = inner.height as usize - 2;
+    let scroll = (selected_row + 1).saturating_sub(visible_rows);
+    let mut text = vec![Line::from(vec![Span::styled(
+        format!(
+            "{:<width$}{}",
+            "",
+            time_axis(window, column_count),
+            width = LABEL_WIDTH
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    )])];
+    text.extend(rows.into_iter().skip(scroll).take(visible_rows));
+    text.push(Line::from(legend));
+
+    frame.render_widget(Paragraph::new(text), inner);
+}
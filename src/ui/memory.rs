@@ -1,192 +1,815 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::app::{App, USED_CHUNKS_HISTORY_SIZE};
+use crate::app::{App, ByteUsage, Forecast, SegmentGroup};
+use crate::types::format_bytes;
+use crate::ui::{format_age, format_duration, format_wall_clock, level_style};
 
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{
+    Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState, Wrap,
+};
 use ratatui::Frame;
 
-use ratatui::widgets::canvas::{Canvas, Line as CanvasLine};
+use std::time::{Duration, Instant};
+
+/// A mempool which is projected to be full within this time is highlighted
+const ETA_WARNING: Duration = Duration::from_secs(60);
 
 pub fn draw<B>(frame: &mut Frame<B>, area: Rect, app: &App)
 where
     B: Backend,
 {
+    // computed in u32 since the product overflows u16 on wide terminals; the result is at most
+    // the width again
+    let max_table_width = u32::from(area.width) * u32::from(MAX_TABLE_PERCENT) / 100;
+    let columns = visible_columns(max_table_width as u16);
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(80), Constraint::Min(0)].as_ref())
+        .constraints(
+            [
+                Constraint::Length(table_width(&columns)),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
         .split(area);
 
+    let graph_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(8)].as_ref())
+        .split(chunks[1]);
+
+    let statistics_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(32)].as_ref())
+        .split(graph_chunks[0]);
+
     draw_mempool_segments(frame, chunks[0], app);
-    draw_graph(frame, chunks[1], app);
+    if app.memory.show_segment_details {
+        draw_segment_details(frame, chunks[1], app);
+        return;
+    }
+    draw_graph(frame, statistics_chunks[0], app);
+    draw_statistics(frame, statistics_chunks[1], app);
+    draw_watermark_raises(frame, graph_chunks[1], app);
+}
+
+/// The widths of the mempool table columns
+const COLUMN_WIDTHS: [u16; 12] = [13, 13, 8, 8, 10, 12, 10, 10, 10, 10, 9, 10];
+
+const COLUMN_NAMES: [&str; 12] = [
+    "MemPool",
+    "Chunks In Use",
+    "Total",
+    "Min Free",
+    "Chunk Size",
+    "Payload Size",
+    "Bytes",
+    "In Use",
+    "Peak",
+    "Overhead",
+    "Chunks/s",
+    "Full in",
+];
+
+/// The columns which are dropped when the table is too wide, the least important first
+const OPTIONAL_COLUMNS: [usize; 7] = [9, 5, 4, 8, 6, 2, 10];
+
+/// The share of the width the table may take; the rest is left for the graph
+const MAX_TABLE_PERCENT: u16 = 60;
+
+fn table_width(columns: &[usize]) -> u16 {
+    // the columns are separated by a space and the table is surrounded by a border
+    columns
+        .iter()
+        .map(|column| COLUMN_WIDTHS[*column])
+        .sum::<u16>()
+        + columns.len() as u16
+        - 1
+        + 2
+}
+
+/// The columns which fit into the width, after dropping the optional ones if necessary
+fn visible_columns(max_width: u16) -> Vec<usize> {
+    let mut columns = (0..COLUMN_WIDTHS.len()).collect::<Vec<_>>();
+    for optional in OPTIONAL_COLUMNS.iter() {
+        if table_width(&columns) <= max_width {
+            break;
+        }
+        columns.retain(|column| column != optional);
+    }
+    columns
+}
+
+/// Keeps the cells of the visible columns; rows with fewer cells leave the last columns empty
+fn visible_cells<'a>(cells: Vec<Cell<'a>>, columns: &[usize]) -> Vec<Cell<'a>> {
+    cells
+        .into_iter()
+        .enumerate()
+        .filter(|(column, _)| columns.contains(column))
+        .map(|(_, cell)| cell)
+        .collect()
+}
+
+fn cell(text: String, column: usize, style: Style) -> Cell<'static> {
+    Cell::from(Span::styled(
+        format!("{:>width$}", text, width = COLUMN_WIDTHS[column] as usize),
+        style,
+    ))
 }
 
 pub fn draw_mempool_segments<B>(frame: &mut Frame<B>, area: Rect, app: &App)
 where
     B: Backend,
 {
-    let mut text = Vec::<Line>::new();
-
     let sample = if let Some(sample) = app.memory.segments.as_ref() {
         sample
     } else {
         return;
     };
 
+    let columns = visible_columns(area.width);
+    let mut rows = Vec::new();
+    let mut selected_row = None;
+
     (*sample).memory_segments().into_iter().for_each(|segment| {
         let segment_id = segment.segment_id();
-        text.push(Line::from(vec![Span::raw("")]));
-        text.push(Line::from(vec![
-            Span::styled(
-                format!("Segment {}", segment_id),
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(format!(
-                " [writer: {} - reader: {}]",
-                segment.writer_group().unwrap_or("##Error##".to_string()),
-                segment.reader_group().unwrap_or("##Error##".to_string())
-            )),
-        ]));
-
-        text.push(Line::from(vec![Span::raw("")]));
-
-        text.push(Line::from(vec![Span::styled(
-            "  MemPool | Chunks In Use |    Total | Min Free | Chunk Size | Payload Size",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]));
+        let collapsed = app.memory.collapsed.contains(&segment_id);
 
-        text.push(Line::from(vec![Span::raw(
-            "  -------------------------------------------------------------------------",
-        )]));
+        let mut mempool_rows = Vec::new();
+        let mut segment_bytes = ByteUsage::default();
+        let (mut used_chunks, mut total_chunks) = (0, 0);
 
         segment
             .mempools()
             .into_iter()
             .enumerate()
             .for_each(|(index, mempool)| {
+                let bytes = ByteUsage::new(
+                    mempool.chunk_size as u64,
+                    mempool.payload_size as u64,
+                    mempool.used_chunks as u64,
+                    mempool.min_free_chunks as u64,
+                    mempool.total_number_of_chunks as u64,
+                );
+                segment_bytes += bytes;
+                used_chunks += mempool.used_chunks as u64;
+                total_chunks += mempool.total_number_of_chunks as u64;
+
+                if collapsed {
+                    return;
+                }
+
                 let style = if app.memory.selection == (segment_id, index) {
+                    selected_row = Some(rows.len() + 1 + mempool_rows.len());
                     Style::default().fg(Color::Yellow)
                 } else {
                     Style::default()
                 };
 
-                let used_chunks_style =
-                    if mempool.used_chunks as f64 / mempool.total_number_of_chunks as f64 > 0.95 {
-                        Style::default().fg(Color::Red)
-                    } else {
-                        style
-                    };
-                let min_free_chunks_style = if mempool.min_free_chunks as f64
-                    / mempool.total_number_of_chunks as f64
-                    > 0.05
-                {
-                    style
+                let thresholds = app.config.thresholds(segment_id, mempool.chunk_size as u64);
+                let total = mempool.total_number_of_chunks as f64;
+                let used_chunks_style = level_style(
+                    thresholds.level(mempool.used_chunks as f64 / total * 100.0),
+                    style,
+                );
+                // the peak usage is derived from the minimal number of free chunks
+                let min_free_chunks_style = level_style(
+                    thresholds.level((total - mempool.min_free_chunks as f64) / total * 100.0),
+                    style,
+                );
+
+                let leak = if app.memory.leak_suspects.contains_key(&(segment_id, index)) {
+                    "↗"
                 } else {
-                    Style::default().fg(Color::Red)
+                    " "
                 };
 
-                text.push(Line::from(vec![
-                    Span::styled(format!("  {:>7} | ", index,), style),
-                    Span::styled(format!("{:>13}", mempool.used_chunks,), used_chunks_style),
-                    Span::styled(
-                        format!(" | {:>8} | ", mempool.total_number_of_chunks),
-                        style,
-                    ),
-                    Span::styled(
-                        format!("{:>8}", mempool.min_free_chunks),
-                        min_free_chunks_style,
-                    ),
-                    Span::styled(
-                        format!(
-                            " | {:>10} | {:>12}",
-                            mempool.chunk_size, mempool.payload_size
-                        ),
-                        style,
-                    ),
-                ]));
+                let forecast = app.memory.forecast((segment_id, index));
+                let rate = forecast
+                    .as_ref()
+                    .map_or("-".to_string(), |forecast| format!("{:+.1}", forecast.rate));
+                let time_to_full = forecast.as_ref().and_then(|forecast| forecast.time_to_full);
+                let time_to_full_style = match time_to_full {
+                    Some(time) if time < ETA_WARNING => Style::default().fg(Color::Red),
+                    _ => style,
+                };
+                let time_to_full = time_to_full.map_or("-".to_string(), format_age);
+
+                let mark = if app.memory.marked.contains(&(segment_id, index)) {
+                    "*"
+                } else {
+                    " "
+                };
+
+                mempool_rows.push(
+                    Row::new(visible_cells(
+                        vec![
+                            Cell::from(Line::from(vec![
+                                Span::styled(mark, style),
+                                Span::styled(leak, Style::default().fg(Color::LightRed)),
+                                Span::styled(
+                                    format!(
+                                        "{:>width$}",
+                                        index,
+                                        width = COLUMN_WIDTHS[0] as usize - 2
+                                    ),
+                                    style,
+                                ),
+                            ])),
+                            cell(mempool.used_chunks.to_string(), 1, used_chunks_style),
+                            cell(mempool.total_number_of_chunks.to_string(), 2, style),
+                            cell(
+                                mempool.min_free_chunks.to_string(),
+                                3,
+                                min_free_chunks_style,
+                            ),
+                            cell(mempool.chunk_size.to_string(), 4, style),
+                            cell(mempool.payload_size.to_string(), 5, style),
+                            cell(format_bytes(bytes.total), 6, style),
+                            cell(format_bytes(bytes.in_use), 7, style),
+                            cell(format_bytes(bytes.peak), 8, style),
+                            cell(format_bytes(bytes.overhead), 9, style),
+                            cell(rate, 10, style),
+                            cell(time_to_full, 11, time_to_full_style),
+                        ],
+                        &columns,
+                    ))
+                    .style(style),
+                );
             });
+
+        // the summary row of a collapsed segment stands for the selection within the segment
+        let mut style = Style::default().add_modifier(Modifier::BOLD);
+        if collapsed && app.memory.selection.0 == segment_id {
+            selected_row = Some(rows.len());
+            style = style.fg(Color::Yellow);
+        }
+        let arrow = if collapsed { "▶" } else { "▼" };
+        let mismatch = if app
+            .roudi_mismatches
+            .iter()
+            .any(|mismatch| mismatch.segment_id == Some(segment_id))
+        {
+            " !"
+        } else {
+            ""
+        };
+        rows.push(
+            Row::new(visible_cells(
+                vec![
+                    Cell::from(Line::from(vec![
+                        Span::styled(format!("{} Segment {}", arrow, segment_id), style),
+                        Span::styled(mismatch, style.fg(Color::Red)),
+                    ])),
+                    cell(used_chunks.to_string(), 1, style),
+                    cell(total_chunks.to_string(), 2, style),
+                    cell(String::new(), 3, style),
+                    cell(String::new(), 4, style),
+                    cell(String::new(), 5, style),
+                    cell(format_bytes(segment_bytes.total), 6, style),
+                    cell(format_bytes(segment_bytes.in_use), 7, style),
+                    cell(format_bytes(segment_bytes.peak), 8, style),
+                    cell(format_bytes(segment_bytes.overhead), 9, style),
+                ],
+                &columns,
+            ))
+            .style(style),
+        );
+        rows.extend(mempool_rows);
     });
 
+    let header = Row::new(
+        columns
+            .iter()
+            .map(|column| cell(COLUMN_NAMES[*column].to_string(), *column, Style::default()))
+            .collect::<Vec<_>>(),
+    )
+    .style(Style::default().add_modifier(Modifier::BOLD))
+    .bottom_margin(1);
+
+    let widths = columns
+        .iter()
+        .map(|column| Constraint::Length(COLUMN_WIDTHS[*column]))
+        .collect::<Vec<_>>();
+
+    let mut title = vec![Span::raw(
+        "Segment & MemPool Info (Space: Mark, s: Mark Segment, c: Clear, Enter: Collapse, \
+         d: Details)",
+    )];
+    if !app.roudi_mismatches.is_empty() {
+        title.push(Span::styled(
+            format!(" ! {} RouDi config mismatches", app.roudi_mismatches.len()),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Line::from(title)),
+        )
+        .widths(&widths);
+
+    // the table scrolls the selected row into view while the header stays on top
+    let mut state = TableState::default();
+    state.select(selected_row);
+
+    let info_lines = segment_info(app);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(0),
+                Constraint::Length(info_lines.len() as u16 + 2),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    frame.render_stateful_widget(table, chunks[0], &mut state);
+
+    let paragraph = Paragraph::new(info_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Segment {}", app.memory.selection.0)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, chunks[1]);
+}
+
+/// The access groups, the memory and the RouDi config mismatches of the selected segment
+fn segment_info(app: &App) -> Vec<Line<'static>> {
+    let mut text = Vec::new();
+
+    let segment_id = app.memory.selection.0;
+    let segment = app.memory.segments.as_ref().and_then(|sample| {
+        sample
+            .memory_segments()
+            .into_iter()
+            .find(|segment| segment.segment_id() == segment_id)
+    });
+    let segment = if let Some(segment) = segment {
+        segment
+    } else {
+        return text;
+    };
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    text.push(Line::from(vec![
+        Span::styled(" Writer: ", bold),
+        Span::raw(segment.writer_group().unwrap_or("##Error##".to_string())),
+        Span::styled("  Reader: ", bold),
+        Span::raw(segment.reader_group().unwrap_or("##Error##".to_string())),
+    ]));
+
+    let mut bytes = ByteUsage::default();
+    segment.mempools().into_iter().for_each(|mempool| {
+        bytes += ByteUsage::new(
+            mempool.chunk_size as u64,
+            mempool.payload_size as u64,
+            mempool.used_chunks as u64,
+            mempool.min_free_chunks as u64,
+            mempool.total_number_of_chunks as u64,
+        )
+    });
+    text.push(Line::from(vec![
+        Span::styled(" Memory: ", bold),
+        Span::raw(format!(
+            "{} in use of {}, peak {} - {} payload + {} overhead",
+            format_bytes(bytes.in_use),
+            format_bytes(bytes.total),
+            format_bytes(bytes.peak),
+            format_bytes(bytes.payload),
+            format_bytes(bytes.overhead)
+        )),
+    ]));
+
+    // the payload sizes of the services are only known from the config
+    if !app.config.service.is_empty() {
+        let services = app.mempool_services(app.memory.selection);
+        text.push(Line::from(vec![
+            Span::styled(format!(" MemPool {} Users: ", app.memory.selection.1), bold),
+            Span::raw(if services.is_empty() {
+                "no service with a fitting payload size".to_string()
+            } else {
+                services
+                    .iter()
+                    .map(|service| {
+                        format!(
+                            "{} • {} • {}",
+                            service.service_id, service.instance_id, service.event_id
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+        ]));
+    }
+
+    // the mismatches of the other segments are marked on their summary rows
+    app.roudi_mismatches
+        .iter()
+        .filter(|mismatch| mismatch.segment_id.is_none() || mismatch.segment_id == Some(segment_id))
+        .for_each(|mismatch| {
+            text.push(Line::from(vec![Span::styled(
+                format!(" ! RouDi config: {}", mismatch.description),
+                Style::default().fg(Color::Red),
+            )]))
+        });
+
+    text
+}
+
+/// Resolves the access groups of the selected segment and checks which processes can use it
+pub fn draw_segment_details<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut text = Vec::<Line>::new();
+
+    let segment_id = app.memory.selection.0;
+    if let Some(segment_access) = app.segment_access.as_ref() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        let mut push_group = |kind: &'static str, segment_group: &SegmentGroup| {
+            let name = segment_group
+                .name
+                .clone()
+                .unwrap_or_else(|| "##Error##".to_string());
+            let group = segment_group.group.as_ref();
+            text.push(Line::from(vec![
+                Span::styled(format!(" {} Group: ", kind), bold),
+                Span::raw(match group {
+                    Some(group) => format!("{} ({})", name, group.gid),
+                    None => name,
+                }),
+            ]));
+            text.push(Line::from(vec![Span::raw(match group {
+                Some(group) if group.members.is_empty() => "   no members".to_string(),
+                Some(group) => format!("   members: {}", group.members.join(", ")),
+                None => "   unknown group on this host".to_string(),
+            })]));
+        };
+        push_group("Writer", &segment_access.writer);
+        push_group("Reader", &segment_access.reader);
+
+        text.push(Line::from(vec![Span::raw("")]));
+        text.push(Line::from(vec![Span::styled(" Access", bold)]));
+
+        let flag = |allowed: bool| {
+            if allowed {
+                Span::styled("yes", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("no ", Style::default().fg(Color::Red))
+            }
+        };
+        for process in segment_access.processes.iter() {
+            text.push(match &process.user {
+                Ok((user, access)) => Line::from(vec![
+                    Span::raw(format!("   {:<32}", format!("{} [{}]", process.name, user))),
+                    Span::raw("read: "),
+                    flag(access.read),
+                    Span::raw("  write: "),
+                    flag(access.write),
+                ]),
+                Err(e) => Line::from(vec![
+                    Span::raw(format!("   {:<32}", process.name)),
+                    Span::styled(format!("unknown ({})", e), Style::default().fg(Color::Red)),
+                ]),
+            });
+        }
+    }
+
     let paragraph = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Segment & MemPool Info"),
+                .title(format!("Segment {} Access (d: Back to Graph)", segment_id)),
         )
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
 }
 
+/// The number of dots of the threshold lines
+const THRESHOLD_DOTS: usize = 60;
+
+const PLOT_COLORS: [Color; 8] = [
+    Color::Yellow,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::LightRed,
+    Color::LightBlue,
+    Color::LightGreen,
+    Color::LightMagenta,
+];
+
+/// The x position of `time` in seconds relative to `now`
+fn relative_time(now: Instant, time: Instant) -> f64 {
+    -(now.duration_since(time).as_secs_f64())
+}
+
+/// The value range of the points within `[start, end]`, including the step which started before
+fn visible_range(points: &[(f64, f64)], start: f64, end: f64) -> Option<(f64, f64)> {
+    let before = points.iter().rev().find(|(x, _)| *x < start);
+    before
+        .into_iter()
+        .chain(points.iter().filter(|(x, _)| *x >= start && *x <= end))
+        .fold(None, |range, (_, y)| match range {
+            Some((low, high)) => Some((f64::min(low, *y), f64::max(high, *y))),
+            None => Some((*y, *y)),
+        })
+}
+
 pub fn draw_graph<B>(frame: &mut Frame<B>, area: Rect, app: &App)
 where
     B: Backend,
 {
-    let (segment, mempool) = app.memory.selection;
+    let mempools = app.memory.graph_mempools();
+
+    let chart_title = match mempools.as_slice() {
+        [(segment, mempool)] => {
+            let forecast = match app.memory.forecast((*segment, *mempool)) {
+                Some(Forecast {
+                    rate,
+                    time_to_full: Some(time),
+                }) => format!(" {:+.1} chunks/s, full in {}", rate, format_age(time)),
+                Some(Forecast { rate, .. }) => format!(" {:+.1} chunks/s", rate),
+                None => String::new(),
+            };
+            format!(
+                "Chunks In Use [Segment {} - MemPool {}]{}",
+                segment, mempool, forecast
+            )
+        }
+        _ => format!("Chunks In Use [{} marked MemPools]", mempools.len()),
+    };
+
+    let chart_title = format!(
+        "{} (+/-: Zoom, [/]: Pan, 0: Reset, e/E: Export)",
+        chart_title
+    );
+
+    // the visible x range in seconds relative to now
+    let start = -(app.graph.offset + app.graph.span).as_secs_f64();
+    let end = -app.graph.offset.as_secs_f64();
+    let now = Instant::now();
+
+    let mut plots = Vec::new();
+    for (index, (segment, mempool)) in mempools.iter().enumerate() {
+        let history = if let Some(history) = app.memory.mempool_history.get(&(*segment, *mempool)) {
+            history
+        } else {
+            continue;
+        };
+
+        // the min/max range and the high-water mark are only shown for a single mempool
+        if mempools.len() == 1 {
+            plots.push((
+                "max".to_string(),
+                Color::DarkGray,
+                GraphType::Line,
+                history.used_chunks.step_points(now, |bucket| bucket.max),
+            ));
+            plots.push((
+                "min".to_string(),
+                Color::DarkGray,
+                GraphType::Line,
+                history.used_chunks.step_points(now, |bucket| bucket.min),
+            ));
+            plots.push((
+                "high-water mark".to_string(),
+                Color::LightRed,
+                GraphType::Line,
+                history
+                    .high_water_mark
+                    .step_points(now, |bucket| bucket.max),
+            ));
+            plots.push((
+                "new high-water mark".to_string(),
+                Color::Red,
+                GraphType::Scatter,
+                history
+                    .watermark_raises
+                    .iter()
+                    .map(|raise| (relative_time(now, raise.time), raise.high_water_mark))
+                    .collect(),
+            ));
+        }
+        plots.push((
+            format!("Segment {} MemPool {}", segment, mempool),
+            PLOT_COLORS[index % PLOT_COLORS.len()],
+            GraphType::Line,
+            history.used_chunks.step_points(now, |bucket| bucket.avg()),
+        ));
+    }
+
+    let shared_thresholds = app.memory.shared_thresholds(&app.config, &mempools);
+
+    // the usage axis includes the thresholds, otherwise they would be clipped
+    let threshold_range =
+        shared_thresholds.map(|thresholds| (thresholds.warning, thresholds.critical));
+    let (low, high) = plots
+        .iter()
+        .filter_map(|(_, _, _, points)| visible_range(points, start, end))
+        .chain(threshold_range)
+        .fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(low, high), (min, max)| (low.min(min), high.max(max)),
+        );
+    let (low, high) = if low <= high {
+        // some headroom which keeps a constant usage visible
+        let margin = ((high - low) * 0.1).max(0.5);
+        ((low - margin).max(0.0), (high + margin).min(100.0))
+    } else {
+        (0.0, 100.0)
+    };
+
+    // a vertical line where the history was restarted after a layout change
+    for mempool in mempools.iter() {
+        let reconfigured = app
+            .memory
+            .mempool_history
+            .get(mempool)
+            .and_then(|history| history.reconfigured)
+            .map(|time| relative_time(now, time));
+        if let Some(x) = reconfigured.filter(|x| *x >= start && *x <= end) {
+            plots.push((
+                "configuration changed".to_string(),
+                Color::Magenta,
+                GraphType::Line,
+                vec![(x, low), (x, high)],
+            ));
+        }
+    }
+
+    if let Some(thresholds) = shared_thresholds {
+        let threshold_line = |value: f64| {
+            // a dotted line to tell it apart from the usage
+            (0..=THRESHOLD_DOTS)
+                .map(|dot| {
+                    (
+                        start + (end - start) * dot as f64 / THRESHOLD_DOTS as f64,
+                        value,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        plots.push((
+            format!("warning {}%", thresholds.warning),
+            Color::Yellow,
+            GraphType::Scatter,
+            threshold_line(thresholds.warning),
+        ));
+        plots.push((
+            format!("critical {}%", thresholds.critical),
+            Color::Red,
+            GraphType::Scatter,
+            threshold_line(thresholds.critical),
+        ));
+    }
+
+    let datasets = plots
+        .iter()
+        .map(|(name, color, graph_type, points)| {
+            Dataset::default()
+                .name(name.as_str())
+                .marker(Marker::Braille)
+                .graph_type(*graph_type)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect();
+
+    let label_style = Style::default().fg(Color::White);
+    let chart = Chart::new(datasets)
+        .block(Block::default().borders(Borders::ALL).title(chart_title))
+        .x_axis(Axis::default().bounds([start, end]).labels(vec![
+            Span::styled(
+                format!("-{}", format_duration(app.graph.offset + app.graph.span)),
+                label_style,
+            ),
+            Span::styled(
+                format!(
+                    "-{}",
+                    format_duration(app.graph.offset + app.graph.span / 2)
+                ),
+                label_style,
+            ),
+            Span::styled(
+                if app.graph.is_zoomed() && end < 0.0 {
+                    format!("-{}", format_duration(app.graph.offset))
+                } else {
+                    "now".to_string()
+                },
+                label_style,
+            ),
+        ]))
+        .y_axis(Axis::default().bounds([low, high]).labels(vec![
+            Span::styled(format!("{:.1}%", low), label_style),
+            Span::styled(format!("{:.1}%", (low + high) / 2.0), label_style),
+            Span::styled(format!("{:.1}%", high), label_style),
+        ]))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+
+    frame.render_widget(chart, area);
+}
+
+pub fn draw_statistics<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut text = Vec::<Line>::new();
+
+    let thresholds = app.thresholds(app.memory.selection);
+    let statistics = app
+        .memory
+        .mempool_history
+        .get(&app.memory.selection)
+        .and_then(|history| {
+            history
+                .used_chunks
+                .statistics(Instant::now(), thresholds.warning)
+        });
 
-    let chart_title = format!("Chunks In Use [Segment {} - MemPool {}]", segment, mempool);
+    if let Some(statistics) = statistics {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let row = |label: &'static str, value: String| {
+            Line::from(vec![
+                Span::styled(format!(" {:<18}", label), bold),
+                Span::raw(value),
+            ])
+        };
+        let percent = |value: f64| format!("{:>6.1}%", value);
 
-    let bottom = -1.0;
-    let left = 0.0;
-    let top = 101.0;
-    let right = USED_CHUNKS_HISTORY_SIZE as f64;
+        text.push(Line::from(vec![Span::raw("")]));
+        text.push(row("Min", percent(statistics.min)));
+        text.push(row("Max", percent(statistics.max)));
+        text.push(row("Mean", percent(statistics.mean)));
+        text.push(row("P50", percent(statistics.p50)));
+        text.push(row("P95", percent(statistics.p95)));
+        text.push(row("P99", percent(statistics.p99)));
+        text.push(row("Std Dev", percent(statistics.std_dev)));
+        text.push(Line::from(vec![Span::raw("")]));
+        text.push(row(
+            "Above Warning",
+            format!("{:>7}", format_age(statistics.time_above_threshold)),
+        ));
+        text.push(row(
+            "Warning Crossings",
+            format!("{:>7}", statistics.crossings),
+        ));
+    }
 
-    let canvas = Canvas::default()
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Statistics [{}]",
+            format_duration(app.memory.history_window)
+        )))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub fn draw_watermark_raises<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut text = Vec::<Line>::new();
+
+    if let Some(history) = app.memory.mempool_history.get(&app.memory.selection) {
+        // newest first
+        history.watermark_raises.iter().rev().for_each(|raise| {
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", format_wall_clock(raise.wall_clock)),
+                    Style::default().fg(Color::Red),
+                ),
+                Span::raw(format!(
+                    "high-water mark {:>5.1}% ({} chunks free)",
+                    raise.high_water_mark, raise.min_free_chunks
+                )),
+            ]));
+        });
+    }
+
+    let paragraph = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(&chart_title as &str),
+                .title("New High-Water Marks"),
         )
-        .paint(|ctx| {
-            if area.width < 4 || area.height < 4 {
-                return;
-            }
-            if let Some(history) = app.memory.used_chunks_history.get(&(segment, mempool)) {
-                let mut pos = USED_CHUNKS_HISTORY_SIZE - history.len();
-                let mut last = None;
-                history.iter().for_each(|value| {
-                    if let Some(last) = last {
-                        ctx.draw(&CanvasLine {
-                            x1: pos as f64,
-                            y1: last,
-                            x2: pos as f64,
-                            y2: *value,
-                            color: Color::Yellow,
-                        });
-                    }
-                    let pos_next = pos + 1;
-                    ctx.draw(&CanvasLine {
-                        x1: pos as f64,
-                        y1: *value,
-                        x2: pos_next as f64,
-                        y2: *value,
-                        color: Color::Yellow,
-                    });
-                    pos = pos_next;
-                    last = Some(*value);
-                });
-            }
-            ctx.print(
-                left,
-                top,
-                Span::styled("100%", Style::default().fg(Color::White)),
-            );
-            ctx.print(
-                left,
-                bottom + (top - bottom) / 2.0,
-                Span::styled("50%", Style::default().fg(Color::White)),
-            );
-            ctx.print(
-                left,
-                bottom,
-                Span::styled("0%", Style::default().fg(Color::White)),
-            );
-        })
-        .x_bounds([left, right])
-        .y_bounds([bottom, top]);
+        .wrap(Wrap { trim: false });
 
-    frame.render_widget(canvas, area);
+    frame.render_widget(paragraph, area);
 }
@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::{App, ChangeKind};
+use crate::types::format_bytes;
+use crate::ui::{format_age, format_duration, level_style};
+
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Sparkline, Wrap};
+use ratatui::Frame;
+
+use std::time::Instant;
+
+const TOP_MEMPOOLS: usize = 5;
+
+pub fn draw<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(8),
+                Constraint::Length(TOP_MEMPOOLS as u16 + 2),
+                Constraint::Min(3),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    let top_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)].as_ref())
+        .split(chunks[0]);
+
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)].as_ref())
+        .split(chunks[2]);
+
+    let warning_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)].as_ref())
+        .split(bottom_chunks[0]);
+
+    draw_counts(frame, top_chunks[0], app);
+    draw_sample_ages(frame, top_chunks[1], app);
+    draw_fullest_mempools(frame, chunks[1], app);
+    draw_unmatched_services(frame, warning_chunks[0], app);
+    draw_leak_suspects(frame, warning_chunks[1], app);
+    draw_recent_changes(frame, bottom_chunks[1], app);
+}
+
+fn draw_counts<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let (publisher_ports, subscriber_ports) =
+        app.services
+            .map
+            .values()
+            .fold((0, 0), |(publishers, subscribers), details| {
+                (
+                    publishers + details.publisher_processes.len(),
+                    subscribers + details.subscriber_processes.len(),
+                )
+            });
+
+    let (segments, mempools) = app.memory.segments.as_ref().map_or((0, 0), |sample| {
+        sample
+            .memory_segments()
+            .into_iter()
+            .fold((0, 0), |(segments, mempools), segment| {
+                (
+                    segments + 1,
+                    mempools + segment.mempools().into_iter().size_hint().0,
+                )
+            })
+    });
+
+    let bytes = app.memory.byte_usage();
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let text = vec![
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::styled(" Processes: ", bold),
+            Span::raw(format!("{}", app.processes.map.len())),
+        ]),
+        Line::from(vec![
+            Span::styled(" Services: ", bold),
+            Span::raw(format!("{}", app.services.map.len())),
+        ]),
+        Line::from(vec![
+            Span::styled(" Ports: ", bold),
+            Span::raw(format!(
+                "{} publisher, {} subscriber",
+                publisher_ports, subscriber_ports
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled(" Memory: ", bold),
+            Span::raw(format!("{} segments, {} mempools", segments, mempools)),
+        ]),
+        Line::from(vec![
+            Span::styled(" Shared Memory: ", bold),
+            Span::raw(format!(
+                "{} in use of {}, peak {}",
+                format_bytes(bytes.in_use),
+                format_bytes(bytes.total),
+                format_bytes(bytes.peak)
+            )),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("System"))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_sample_ages<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let now = Instant::now();
+    let age = |last_sample: Option<Instant>| match last_sample {
+        Some(time) => Span::raw(format_age(now - time)),
+        None => Span::styled("no sample yet", Style::default().fg(Color::Red)),
+    };
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let text = vec![
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::styled(" MemPool: ", bold),
+            age(app.memory.last_sample),
+        ]),
+        Line::from(vec![
+            Span::styled(" Process: ", bold),
+            age(app.processes.last_sample),
+        ]),
+        Line::from(vec![
+            Span::styled(" Port: ", bold),
+            age(app.services.last_sample),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Age of last Sample"),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_fullest_mempools<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Top {} MemPools by Usage", TOP_MEMPOOLS));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mempools = app.memory.mempools_by_usage();
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); TOP_MEMPOOLS])
+        .split(inner);
+
+    for (mempool, row) in mempools.iter().zip(rows.iter()) {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(40), Constraint::Min(0)].as_ref())
+            .split(*row);
+
+        let usage = mempool.usage();
+        let level = app
+            .config
+            .thresholds(mempool.segment_id, mempool.chunk_size)
+            .level(usage * 100.0);
+        let style = level_style(level, Style::default());
+        let label = Paragraph::new(Line::from(vec![Span::styled(
+            format!(
+                " Segment {} MemPool {:>2} {:>6.1}% {:>6}/{:<6}",
+                mempool.segment_id,
+                mempool.index,
+                usage * 100.0,
+                mempool.used_chunks,
+                mempool.total_number_of_chunks
+            ),
+            style,
+        )]));
+        frame.render_widget(label, columns[0]);
+
+        let history: Vec<u64> = app
+            .memory
+            .mempool_history
+            .get(&(mempool.segment_id, mempool.index))
+            .map(|history| {
+                history
+                    .used_chunks
+                    .iter()
+                    .map(|bucket| bucket.avg().round() as u64)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let visible = history.len().saturating_sub(columns[1].width as usize);
+        let sparkline = Sparkline::default()
+            .data(&history[visible..])
+            .max(100)
+            .style(Style::default().fg(Color::Yellow));
+        frame.render_widget(sparkline, columns[1]);
+    }
+}
+
+fn draw_unmatched_services<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut text = Vec::<Line>::new();
+    text.push(Line::from(vec![Span::raw("")]));
+
+    for (service, missing) in app.services.unmatched() {
+        text.push(Line::from(vec![
+            Span::raw(format!(
+                " • {} • {} • {} ",
+                service.service_id, service.instance_id, service.event_id
+            )),
+            Span::styled(format!("({})", missing), Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Services without Counterpart"),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_leak_suspects<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut text = Vec::<Line>::new();
+    text.push(Line::from(vec![Span::raw("")]));
+
+    for ((segment_id, index), suspect) in app.memory.leak_suspects.iter() {
+        text.push(Line::from(vec![
+            Span::raw(format!(" • Segment {} MemPool {} ", segment_id, index)),
+            Span::styled(
+                format!("(lowest usage {:.1}% → {:.1}%)", suspect.start, suspect.end),
+                Style::default().fg(Color::LightRed),
+            ),
+        ]));
+    }
+
+    let title = match app.memory.leak_detector.as_ref() {
+        Some(leak_detector) => format!(
+            "Suspected Leaks (steady Growth within {})",
+            format_duration(leak_detector.window)
+        ),
+        None => "Suspected Leaks".to_string(),
+    };
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_recent_changes<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let mut changes = app
+        .processes
+        .changes
+        .iter()
+        .chain(app.services.changes.iter())
+        .chain(app.memory.changes.iter())
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|change| std::cmp::Reverse(change.time));
+
+    let now = Instant::now();
+    let mut text = Vec::<Line>::new();
+    text.push(Line::from(vec![Span::raw("")]));
+
+    for change in changes {
+        let (marker, style) = match change.kind {
+            ChangeKind::Appeared => ("+", Style::default().fg(Color::Green)),
+            ChangeKind::Vanished => ("-", Style::default().fg(Color::Red)),
+            ChangeKind::Reconfigured => ("~", Style::default().fg(Color::Magenta)),
+        };
+        text.push(Line::from(vec![
+            Span::styled(format!(" {} ", marker), style),
+            Span::raw(format!(
+                "{:>10} ago  {}",
+                format_age(now - change.time),
+                change.name
+            )),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Recent Changes"),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
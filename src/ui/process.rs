@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::app::App;
+use crate::proc_info::state_name;
+use crate::types::format_bytes;
+use crate::ui::{format_age, format_wall_clock};
 
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
@@ -31,32 +34,66 @@ where
     text.push(Line::from(vec![Span::raw("")]));
 
     text.push(Line::from(vec![Span::styled(
-        "    PID | Publisher | Subscriber | Nodes | Process Name",
+        "    PID |   CPU |       RSS | Threads |      Uptime | Pub | Sub | Nodes | Process Name",
         Style::default().add_modifier(Modifier::BOLD),
     )]));
 
     text.push(Line::from(vec![Span::raw(
-        " ----------------------------------------------------------",
+        " -------------------------------------------------------------------------------------",
     )]));
 
-    for (index, (process_name, details)) in app.processes.map.iter().enumerate() {
+    for (index, (process_name, details)) in app.processes.sorted().into_iter().enumerate() {
         let style = if app.processes.selection.0 == index {
             Style::default().fg(Color::Yellow)
         } else {
             Style::default()
         };
 
+        let info = details.info.as_ref();
         text.push(Line::from(vec![
             Span::styled(format!(" {:>6} | ", details.pid), style),
-            Span::styled(format!("{:>9} | ", details.publisher_ports.len()), style),
-            Span::styled(format!("{:>10} | ", details.subscriber_ports.len()), style),
-            Span::styled(format!("{:>5} | ", details.nodes.len()), style),
+            Span::styled(
+                format!(
+                    "{:>5} | ",
+                    details
+                        .cpu_usage
+                        .map_or("-".to_string(), |cpu| format!("{:.0}%", cpu))
+                ),
+                style,
+            ),
+            Span::styled(
+                format!(
+                    "{:>9} | ",
+                    info.map_or("-".to_string(), |info| format_bytes(info.rss))
+                ),
+                style,
+            ),
+            Span::styled(
+                format!(
+                    "{:>7} | ",
+                    info.map_or("-".to_string(), |info| info.threads.to_string())
+                ),
+                style,
+            ),
+            Span::styled(
+                format!(
+                    "{:>11} | ",
+                    info.map_or("-".to_string(), |info| format_age(info.uptime()))
+                ),
+                style,
+            ),
+            Span::styled(format!("{:>3} | ", details.publisher_ports.len()), style),
+            Span::styled(format!("{:>3} | ", details.subscriber_ports.len()), style),
+            Span::styled(format!("{:>5} | ", details.node_count), style),
             Span::styled(format!("{}", process_name), style),
         ]));
     }
 
     let paragraph = Paragraph::new(text)
-        .block(Block::default().borders(Borders::ALL).title("Process List"))
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Process List (o: Sort by {})",
+            app.processes.sort.name()
+        )))
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
@@ -82,6 +119,45 @@ where
             Span::raw(format!("{:}", details.pid)),
         ]));
 
+        match details.info.as_ref() {
+            Some(info) => {
+                let fields = vec![
+                    (" Command: ", info.cmdline.clone()),
+                    (
+                        " User: ",
+                        match info.user.as_ref() {
+                            Some(user) => format!("{} ({})", user, info.uid),
+                            None => info.uid.to_string(),
+                        },
+                    ),
+                    (
+                        " State: ",
+                        format!("{} ({})", state_name(info.state), info.state),
+                    ),
+                    (" Started: ", format_wall_clock(info.start_time)),
+                    (" Uptime: ", format_age(info.uptime())),
+                    (
+                        " CPU: ",
+                        details
+                            .cpu_usage
+                            .map_or("-".to_string(), |cpu| format!("{:.1}%", cpu)),
+                    ),
+                    (" RSS: ", format_bytes(info.rss)),
+                    (" Threads: ", info.threads.to_string()),
+                ];
+                for (name, value) in fields {
+                    text.push(Line::from(vec![
+                        Span::styled(name, Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(value),
+                    ]));
+                }
+            }
+            None => text.push(Line::from(vec![Span::styled(
+                " No data in /proc for this PID",
+                Style::default().fg(Color::Red),
+            )])),
+        }
+
         text.push(Line::from(vec![
             Span::styled(
                 " Publisher Ports: ",
@@ -112,13 +188,23 @@ where
 
         text.push(Line::from(vec![
             Span::styled(" Nodes: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{:}", details.nodes.len())),
+            Span::raw(format!("{:}", details.node_count)),
         ]));
-        if details.nodes.len() >= 1 {
-            text.push(Line::from(vec![Span::styled(
-                "     Listing nodes needs implementation!",
-                Style::default().fg(Color::Red),
-            )]));
+        // only the nodes which own ports are known by name
+        for (node_name, node) in details.nodes.iter() {
+            text.push(Line::from(vec![Span::raw(format!(" • {}", node_name))]));
+            for port in node.publisher_ports.iter() {
+                text.push(Line::from(vec![Span::raw(format!(
+                    "     Publisher: {} • {} • {}",
+                    port.service_id, port.instance_id, port.event_id
+                ))]));
+            }
+            for port in node.subscriber_ports.iter() {
+                text.push(Line::from(vec![Span::raw(format!(
+                    "     Subscriber: {} • {} • {}",
+                    port.service_id, port.instance_id, port.event_id
+                ))]));
+            }
         }
     }
 
@@ -128,7 +214,7 @@ where
                 .borders(Borders::ALL)
                 .title("Process Details"),
         )
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
 }
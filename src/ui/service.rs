@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::app::App;
+use crate::types::format_bytes;
 
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
@@ -96,6 +97,45 @@ where
         for process in details.subscriber_processes.iter() {
             text.push(Line::from(vec![Span::raw(format!(" • {}", process))]));
         }
+
+        text.push(Line::from(vec![Span::styled(
+            "Likely MemPools: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]));
+
+        let payload_size = app
+            .config
+            .service
+            .iter()
+            .find(|service_config| service_config.service.matches(&app.services.selection.1))
+            .map(|service_config| service_config.payload_size);
+        let mempools = app
+            .service_mempools
+            .get(&app.services.selection.1)
+            .cloned()
+            .unwrap_or_default();
+        match payload_size {
+            None => text.push(Line::from(vec![Span::raw(
+                " • unknown payload size, which can be set in the config",
+            )])),
+            Some(payload_size) if mempools.is_empty() => text.push(Line::from(vec![Span::styled(
+                format!(
+                    " • no mempool of a writable segment fits the payload of {}",
+                    format_bytes(payload_size)
+                ),
+                Style::default().fg(Color::Red),
+            )])),
+            Some(payload_size) => {
+                for (segment_id, index) in mempools {
+                    text.push(Line::from(vec![Span::raw(format!(
+                        " • Segment {} MemPool {} for a payload of {}",
+                        segment_id,
+                        index,
+                        format_bytes(payload_size)
+                    ))]));
+                }
+            }
+        }
     }
 
     let paragraph = Paragraph::new(text)
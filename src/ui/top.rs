@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::App;
+use crate::ui::{format_age, level_style};
+
+use ratatui::backend::Backend;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use std::convert::TryFrom;
+use std::time::Instant;
+
+const USAGE_BAR_WIDTH: usize = 20;
+
+/// The number of lines of the summary with `mempools` mempools
+pub fn height(mempools: usize) -> u16 {
+    // the terminal clips a summary which is too high
+    u16::try_from(mempools)
+        .unwrap_or(u16::MAX)
+        .saturating_add(2)
+}
+
+/// A compact summary with the number of processes, the fullest mempools and unmatched services
+pub fn summary(app: &App, mempools: usize) -> Vec<Line<'static>> {
+    let mut text = Vec::<Line>::new();
+
+    let unmatched = app.services.unmatched();
+    let last_sample = [
+        app.memory.last_sample,
+        app.processes.last_sample,
+        app.services.last_sample,
+    ]
+    .iter()
+    .flatten()
+    .max()
+    .map_or("no sample yet".to_string(), |time| {
+        format!("last sample {} ago", format_age(Instant::now() - *time))
+    });
+
+    text.push(Line::from(vec![
+        Span::styled(" iceray", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(
+            " │ {} processes │ {} services │ ",
+            app.processes.map.len(),
+            app.services.map.len()
+        )),
+        Span::styled(
+            format!("{} unmatched", unmatched.len()),
+            if unmatched.is_empty() {
+                Style::default()
+            } else {
+                Style::default().fg(Color::Red)
+            },
+        ),
+        Span::raw(format!(" │ {}", last_sample)),
+    ]));
+
+    let fullest = app.memory.mempools_by_usage();
+    for index in 0..mempools {
+        let mempool = if let Some(mempool) = fullest.get(index) {
+            mempool
+        } else {
+            text.push(Line::from(vec![Span::raw("")]));
+            continue;
+        };
+
+        let usage = mempool.usage();
+        let filled = ((usage * USAGE_BAR_WIDTH as f64).round() as usize).min(USAGE_BAR_WIDTH);
+        let level = app
+            .config
+            .thresholds(mempool.segment_id, mempool.chunk_size)
+            .level(usage * 100.0);
+        let style = level_style(level, Style::default().fg(Color::Yellow));
+        text.push(Line::from(vec![
+            Span::raw(format!(
+                " Segment {} MemPool {:>2} [",
+                mempool.segment_id, mempool.index
+            )),
+            Span::styled("█".repeat(filled), style),
+            Span::raw(format!(
+                "{}] {:>5.1}% {:>6}/{:<6}",
+                "░".repeat(USAGE_BAR_WIDTH - filled),
+                usage * 100.0,
+                mempool.used_chunks,
+                mempool.total_number_of_chunks
+            )),
+        ]));
+    }
+
+    let unmatched_services = unmatched
+        .iter()
+        .map(|(service, missing)| {
+            format!(
+                "{} • {} • {} ({})",
+                service.service_id, service.instance_id, service.event_id, missing
+            )
+        })
+        .collect::<Vec<_>>();
+    text.push(Line::from(vec![Span::raw(
+        if unmatched_services.is_empty() {
+            " all services matched".to_string()
+        } else {
+            format!(" unmatched: {}", unmatched_services.join(", "))
+        },
+    )]));
+
+    text
+}
+
+pub fn draw<B>(frame: &mut Frame<B>, area: Rect, app: &App, mempools: usize)
+where
+    B: Backend,
+{
+    frame.render_widget(Paragraph::new(summary(app, mempools)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn height_saturates() {
+        assert_eq!(height(3), 5);
+        assert_eq!(height(u16::MAX as usize - 2), u16::MAX);
+        assert_eq!(height(usize::MAX), u16::MAX);
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::App;
+use crate::topology::TopologyNode;
+
+use ratatui::backend::Backend;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// A character grid on which the edges are drawn as connection masks and the nodes as text
+struct Grid {
+    width: usize,
+    masks: Vec<u8>,
+    cells: Vec<Option<(char, Style)>>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            masks: vec![0; width * height],
+            cells: vec![None; width * height],
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.masks.len() / self.width
+    }
+
+    fn horizontal(&mut self, y: usize, x1: usize, x2: usize) {
+        let (x1, x2) = if x1 <= x2 { (x1, x2) } else { (x2, x1) };
+        for x in x1..=x2 {
+            let mut mask = 0;
+            if x > x1 {
+                mask |= LEFT;
+            }
+            if x < x2 {
+                mask |= RIGHT;
+            }
+            // single cells still need to be drawn as line
+            if mask == 0 {
+                mask = LEFT | RIGHT;
+            }
+            self.masks[y * self.width + x] |= mask;
+        }
+    }
+
+    fn vertical(&mut self, x: usize, y1: usize, y2: usize) {
+        let (y1, y2) = if y1 <= y2 { (y1, y2) } else { (y2, y1) };
+        for y in y1..=y2 {
+            let mut mask = 0;
+            if y > y1 {
+                mask |= UP;
+            }
+            if y < y2 {
+                mask |= DOWN;
+            }
+            self.masks[y * self.width + x] |= mask;
+        }
+    }
+
+    fn print(&mut self, x: usize, y: usize, text: &str, max_width: usize, style: Style) {
+        for (offset, c) in text.chars().take(max_width).enumerate() {
+            if x + offset < self.width {
+                self.cells[y * self.width + x + offset] = Some((c, style));
+            }
+        }
+    }
+
+    fn line(&self, y: usize) -> Line<'static> {
+        let mut spans = Vec::new();
+        let mut text = String::new();
+        let mut current_style = Style::default();
+
+        for x in 0..self.width {
+            let (c, style) = self.cells[y * self.width + x].unwrap_or_else(|| {
+                (
+                    box_drawing_char(self.masks[y * self.width + x]),
+                    Style::default().fg(Color::DarkGray),
+                )
+            });
+            if style != current_style && !text.is_empty() {
+                spans.push(Span::styled(text.clone(), current_style));
+                text.clear();
+            }
+            current_style = style;
+            text.push(c);
+        }
+        spans.push(Span::styled(text, current_style));
+
+        Line::from(spans)
+    }
+}
+
+fn box_drawing_char(mask: u8) -> char {
+    match mask {
+        0 => ' ',
+        m if m == LEFT || m == RIGHT || m == LEFT | RIGHT => '─',
+        m if m == UP || m == DOWN || m == UP | DOWN => '│',
+        m if m == DOWN | RIGHT => '┌',
+        m if m == DOWN | LEFT => '┐',
+        m if m == UP | RIGHT => '└',
+        m if m == UP | LEFT => '┘',
+        m if m == UP | DOWN | RIGHT => '├',
+        m if m == UP | DOWN | LEFT => '┤',
+        m if m == DOWN | LEFT | RIGHT => '┬',
+        m if m == UP | LEFT | RIGHT => '┴',
+        _ => '┼',
+    }
+}
+
+pub fn draw<B>(frame: &mut Frame<B>, area: Rect, app: &App)
+where
+    B: Backend,
+{
+    let topology = app.topology.graph(&app.processes, &app.services);
+    let layers = topology.layers();
+    let selected = layers.iter().flatten().nth(app.topology.selection);
+
+    let title = match app.topology.focus.as_ref() {
+        Some((node, hops)) => format!(
+            "Topology [Focus: {} - {} Hop(s)] (Enter: Toggle Hops, Esc: Reset)",
+            node.label(),
+            hops
+        ),
+        None => "Topology (Enter: Focus)".to_string(),
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if inner.width < 20 || inner.height < 3 {
+        return;
+    }
+
+    // three columns with the publishing processes, services and subscribing processes;
+    // the gaps in between are used to route the edges
+    let width = inner.width as usize;
+    let gap = (width / 8).max(5);
+    let column_width = (width - 2 * gap) / 3;
+    let column_x = [0, column_width + gap, 2 * (column_width + gap)];
+    let height = 1 + layers.iter().map(|layer| layer.len()).max().unwrap_or(0);
+
+    let mut grid = Grid::new(width, height);
+
+    let row_of = |layer: usize, node: &TopologyNode| {
+        layers[layer]
+            .iter()
+            .position(|entry| entry == node)
+            .map(|index| index + 1)
+    };
+
+    let mut draw_edge = |layer: usize, source: &TopologyNode, target: &TopologyNode| {
+        let (source_row, target_row) = match (row_of(layer, source), row_of(layer + 1, target)) {
+            (Some(source_row), Some(target_row)) => (source_row, target_row),
+            _ => return,
+        };
+
+        // every source gets its own lane to keep the vertical lines apart
+        let source_end = column_x[layer] + column_width;
+        let target_start = column_x[layer + 1];
+        let lane_count = target_start - source_end - 3;
+        let lane = source_end + 1 + (source_row - 1) % lane_count;
+
+        grid.horizontal(source_row, source_end, lane);
+        grid.vertical(lane, source_row, target_row);
+        grid.horizontal(target_row, lane, target_start - 2);
+        grid.print(
+            target_start - 1,
+            target_row,
+            "►",
+            1,
+            Style::default().fg(Color::DarkGray),
+        );
+    };
+
+    for (process, service) in topology.publishers.keys() {
+        draw_edge(
+            0,
+            &TopologyNode::Process(process.clone()),
+            &TopologyNode::Service(service.clone()),
+        );
+    }
+    for (service, process) in topology.subscribers.keys() {
+        draw_edge(
+            1,
+            &TopologyNode::Service(service.clone()),
+            &TopologyNode::Process(process.clone()),
+        );
+    }
+
+    let header_style = Style::default().add_modifier(Modifier::BOLD);
+    grid.print(column_x[0], 0, "Publishers", column_width, header_style);
+    grid.print(column_x[1], 0, "Services", column_width, header_style);
+    grid.print(column_x[2], 0, "Subscribers", column_width, header_style);
+
+    let mut selected_row = 0;
+    let mut flat_index = 0;
+    for (layer, nodes) in layers.iter().enumerate() {
+        for (index, node) in nodes.iter().enumerate() {
+            let (label, mut style) = match node {
+                TopologyNode::Process(name) => (format!("[{}]", name), Style::default()),
+                TopologyNode::Service(name) => {
+                    (format!("({})", name), Style::default().fg(Color::Cyan))
+                }
+            };
+            if flat_index == app.topology.selection {
+                selected_row = index + 1;
+            }
+            // processes in both process layers are highlighted twice
+            if Some(node) == selected {
+                style = Style::default().fg(Color::Yellow);
+            }
+            if app.topology.focus.as_ref().map(|(focus, _)| focus) == Some(node) {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            grid.print(column_x[layer], index + 1, &label, column_width, style);
+            flat_index += 1;
+        }
+    }
+
+    // the header stays on top while the selected row is scrolled into view
+    let visible_rows = inner.height as usize - 1;
+    let scroll = selected_row.saturating_sub(visible_rows);
+    let mut text = vec![grid.line(0)];
+    text.extend(
+        (1 + scroll..grid.height())
+            .take(visible_rows)
+            .map(|y| grid.line(y)),
+    );
+
+    frame.render_widget(Paragraph::new(text), inner);
+}
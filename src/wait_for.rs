@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::app::{ProcessList, ServiceList};
+use crate::params::WaitForParams;
+use crate::runtime;
+
+use std::time::Duration;
+
+/// Polls the process and port introspection until all conditions of `params` are met.
+/// Returns the unmet conditions if the timeout elapsed before.
+pub fn run(params: &WaitForParams, poll_interval: Duration) -> Result<(), Vec<String>> {
+    let mut processes = ProcessList::new();
+    let mut services = ServiceList::new();
+
+    let mut unmet = Vec::new();
+    let met = runtime::sample_for(params.timeout, poll_interval, || {
+        processes.update();
+        services.update(&mut processes);
+
+        unmet = unmet_conditions(params, &processes, &services);
+        unmet.is_empty()
+    });
+
+    if met {
+        Ok(())
+    } else {
+        Err(unmet)
+    }
+}
+
+fn unmet_conditions(
+    params: &WaitForParams,
+    processes: &ProcessList,
+    services: &ServiceList,
+) -> Vec<String> {
+    let mut unmet = Vec::new();
+
+    for process in params.process.iter() {
+        if !processes.map.contains_key(process) {
+            unmet.push(format!("process '{}' is not registered", process));
+        }
+    }
+
+    for service in params.service.iter() {
+        let details = services
+            .map
+            .iter()
+            .find(|(description, _)| service.matches(description))
+            .map(|(_, details)| details);
+
+        let details = if let Some(details) = details {
+            details
+        } else {
+            unmet.push(format!("service '{}' is not available", service));
+            continue;
+        };
+
+        let publishers = details.publisher_processes.len();
+        let subscribers = details.subscriber_processes.len();
+        if publishers < params.publishers || subscribers < params.subscribers {
+            unmet.push(format!(
+                "service '{}' has {} of {} publishers and {} of {} subscribers",
+                service, publishers, params.publishers, subscribers, params.subscribers
+            ));
+        }
+    }
+
+    unmet
+}